@@ -0,0 +1,155 @@
+use crate::solver::{beside_root, DEFAULT_YEAR};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, ErrorKind},
+};
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.txt";
+
+// answers.txt holds 2019, other years each get their own file next to it
+fn file_name(year: i32) -> String {
+    if year == DEFAULT_YEAR {
+        String::from(DEFAULT_ANSWERS_FILE)
+    } else {
+        format!("answers-{}.txt", year)
+    }
+}
+
+// Kept next to the input root, like the input/ and answers.txt of this repository
+pub fn default_file(root: &str, year: i32) -> String {
+    beside_root(root, &file_name(year))
+}
+
+// The answers for an input set are kept with its inputs
+pub fn set_file(dir: &str, year: i32) -> String {
+    format!("{}/{}", dir, file_name(year))
+}
+
+/* Expected answers, one per line as "<day> <part> <answer>". Blank lines and
+ * lines starting with '#' are skipped, answers spanning several lines are
+ * written with "\n" in place of the line breaks and "\\" for a backslash. A
+ * space ending the answer is written as "\s" so that editors don't strip it.
+ */
+#[derive(Default)]
+pub struct Answers {
+    answers: BTreeMap<(i32, u8), String>,
+}
+
+impl Answers {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in fs::read_to_string(path)?.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut split = line.splitn(3, ' ');
+            let day = split.next().and_then(|d| d.parse().ok());
+            let part = split.next().and_then(|p| p.parse().ok());
+            match (day, part, split.next()) {
+                (Some(day), Some(part), Some(answer)) => { answers.insert((day, part), unescape(answer)); },
+                _ => return Err(io::Error::new(ErrorKind::InvalidData, format!("{}:{}: expected <day> <part> <answer>", path, i + 1))),
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    // A missing file just means there aren't any answers yet
+    pub fn load_or_default(path: &str) -> io::Result<Self> {
+        match Self::load(path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            a => a,
+        }
+    }
+
+    pub fn get(&self, day: i32, part: u8) -> Option<&String> {
+        self.answers.get(&(day, part))
+    }
+
+    pub fn set(&mut self, day: i32, part: u8, answer: &str) {
+        self.answers.insert((day, part), answer.to_string());
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut contents = String::from("# <day> <part> <answer>, line breaks in answers are written as \\n and a final space as \\s\n");
+        for ((day, part), answer) in self.answers.iter() {
+            contents.push_str(&format!("{:02} {} {}\n", day, part, escape(answer)));
+        }
+        fs::write(path, contents)
+    }
+}
+
+fn escape(s: &str) -> String {
+    let escaped = s.replace('\\', "\\\\").replace('\n', "\\n");
+    // Once the last space is escaped none of the others are trailing either
+    match escaped.strip_suffix(' ') {
+        Some(rest) => format!("{}\\s", rest),
+        None => escaped,
+    }
+}
+
+fn unescape(s: &str) -> String {
+    let mut output = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => { output.push('\n'); chars.next(); },
+            ('\\', Some('\\')) => { output.push('\\'); chars.next(); },
+            ('\\', Some('s')) => { output.push(' '); chars.next(); },
+            (c, _) => output.push(c),
+        }
+    }
+    output
+}
+
+// Describes how the actual answer differs from the expected one, None if they match
+pub fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    if !expected.contains('\n') && !actual.contains('\n') {
+        return Some(format!("    expected: {}\n    actual:   {}\n", expected, actual));
+    }
+
+    let expected_lines: Vec<_> = expected.lines().collect();
+    let actual_lines: Vec<_> = actual.lines().collect();
+    let mut output = String::new();
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(e), Some(a)) if e == a => output.push_str(&format!("      {}\n", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    output.push_str(&format!("    - {}\n", e));
+                }
+                if let Some(a) = a {
+                    output.push_str(&format!("    + {}\n", a));
+                }
+            }
+        }
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_round_trip() {
+        for answer in ["12", "a\nb", "back\\slash", "\\n is not a line break", " #  # \n#  #  ", "ends with \\"] {
+            let escaped = escape(answer);
+            assert!(!escaped.contains('\n') && !escaped.ends_with(' '), "{:?} escaped to {:?}", answer, escaped);
+            assert_eq!(unescape(&escaped), answer);
+        }
+        assert_eq!(escape("a\\b\nc  "), "a\\\\b\\nc \\s");
+        assert_eq!(unescape("x\\sy\\q"), "x y\\q");
+    }
+
+    #[test]
+    fn diffs() {
+        assert_eq!(diff("12", "12"), None);
+        assert_eq!(diff("#.\n.#", "#.\n.#"), None);
+        assert_eq!(diff("12", "13"), Some(String::from("    expected: 12\n    actual:   13\n")));
+        assert_eq!(diff("#.\n.#\n##", "#.\n##"), Some(String::from("      #.\n    - .#\n    + ##\n    - ##\n")));
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{
+    grid::Grid,
+    intcode::{parse_program, BufferedIO, IntCodeMachine},
+    registry,
+    solutions::day18,
+    solver::{input_file, Solver, DEFAULT_YEAR},
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    hint::black_box,
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
+
+const USAGE: &str = "\
+Usage: bench [FILTER] [OPTIONS]
+
+Benchmarks parsing and both parts of every day, and the shared hot paths
+intcode/run, grid/from_reader and day18/search. Only the benchmarks whose name
+contains FILTER are run, e.g. day07, part2 or intcode. The slow days need
+minutes per sample even in release builds.
+
+Options:
+  --year <YEAR>          benchmark the days of YEAR, 2019 by default
+  --samples <N>          samples to take of each benchmark, 20 by default
+  --warm-up <SECS>       how long to run each benchmark before sampling, 1
+  --measure <SECS>       roughly how long to spend sampling each one, 3
+  --baseline <NAME>      compare with target/bench/NAME.txt of this crate, or
+                         with the file if NAME is a path, base by default, and
+                         save to it unless something regressed
+  --no-save              only compare, keep the baseline as it is
+  --save-baseline        save the baseline even if something regressed
+  --threshold <PERCENT>  flag means slower than the baseline by more than
+                         this, 5 by default
+  --sigmas <K>           and by more than K of the baseline's standard
+                         deviations, 2 by default";
+
+const BASELINE_DIR: &str = "target/bench";
+// Below this the spread between samples says little
+const MIN_SAMPLES: usize = 3;
+
+// The example with the most keys to juggle that still finishes quickly
+const MAZE_EXAMPLE: &str = "\
+#################
+#i.G..c...e..H.p#
+########.########
+#j.A..b...f..D.o#
+########@########
+#k.E..a...g..B.n#
+########.########
+#l.F..d...h..C.m#
+#################";
+
+pub struct Stats {
+    // All in nanoseconds per iteration
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub samples: usize,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+        let median = match samples.len() % 2 {
+            0 => (samples[samples.len() / 2 - 1] + samples[samples.len() / 2]) / 2.0,
+            _ => samples[samples.len() / 2],
+        };
+        Self { mean, median, std_dev: variance.sqrt(), min: samples[0], max: samples[samples.len() - 1], samples: samples.len() }
+    }
+}
+
+fn format_nanos(ns: f64) -> String {
+    match ns {
+        ns if ns >= 1e9 => format!("{:.3}s", ns / 1e9),
+        ns if ns >= 1e6 => format!("{:.3}ms", ns / 1e6),
+        ns if ns >= 1e3 => format!("{:.3}us", ns / 1e3),
+        ns => format!("{:.1}ns", ns),
+    }
+}
+
+pub struct Bencher {
+    filter: Option<String>,
+    samples: usize,
+    warm_up: Duration,
+    measure: Duration,
+    threshold: f64,
+    sigmas: f64,
+    // Mean and standard deviation of each benchmark the last time it was saved
+    baseline: HashMap<String, (f64, f64)>,
+    results: Vec<(String, Stats)>,
+    regressions: usize,
+}
+
+impl Bencher {
+    pub fn wants(&self, name: &str) -> bool {
+        self.filter.as_ref().is_none_or(|f| name.contains(f.as_str()))
+    }
+
+    /* Runs f until the warm-up is over, then uses how long that took to pick
+     * how many times to run it per sample so each sample is long enough to time.
+     */
+    pub fn bench<T>(&mut self, name: &str, mut f: impl FnMut() -> T) {
+        if !self.wants(name) {
+            return;
+        }
+
+        let start = Instant::now();
+        let mut warm_up_iterations = 0;
+        while warm_up_iterations == 0 || start.elapsed() < self.warm_up {
+            black_box(f());
+            warm_up_iterations += 1;
+        }
+        let per_iteration = start.elapsed().as_nanos() as f64 / warm_up_iterations as f64;
+
+        let budget = self.measure.as_nanos() as f64;
+        let samples = ((budget / per_iteration) as usize).clamp(MIN_SAMPLES, self.samples.max(MIN_SAMPLES));
+        let iterations = ((budget / samples as f64 / per_iteration) as usize).max(1);
+
+        let mut times = vec!();
+        for _ in 0..samples {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(f());
+            }
+            times.push(start.elapsed().as_nanos() as f64 / iterations as f64);
+        }
+
+        let stats = Stats::from_samples(times);
+        let mut line = format!("{:<24} {:>12} ± {:<12} median {:>12}  [{} .. {}]  {}x{}",
+                               name, format_nanos(stats.mean), format_nanos(stats.std_dev),
+                               format_nanos(stats.median), format_nanos(stats.min), format_nanos(stats.max),
+                               stats.samples, iterations);
+        if let Some(&(mean, std_dev)) = self.baseline.get(name) {
+            line.push_str(&format!("  {:+.1}%", (stats.mean - mean) / mean * 100.0));
+            if regressed(stats.mean, (mean, std_dev), self.threshold, self.sigmas) {
+                self.regressions += 1;
+                line.push_str(" REGRESSED");
+            }
+        }
+        println!("{}", line);
+        self.results.push((name.to_string(), stats));
+    }
+}
+
+// Noisy benchmarks need to be slower by more than their usual spread as well as by the threshold
+fn regressed(mean: f64, (base_mean, base_std_dev): (f64, f64), threshold: f64, sigmas: f64) -> bool {
+    let change = (mean - base_mean) / base_mean * 100.0;
+    change > threshold && mean > base_mean + sigmas * base_std_dev
+}
+
+// Names go in the target/ of this crate wherever it is run from, paths are used as they are
+fn baseline_path(name: &str) -> PathBuf {
+    if name.contains(std::path::MAIN_SEPARATOR) || name.contains('/') {
+        PathBuf::from(name)
+    } else {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(BASELINE_DIR).join(format!("{}.txt", name))
+    }
+}
+
+// One benchmark per line as "<name> <mean ns> <std dev ns>"
+fn load_baseline(name: &str) -> HashMap<String, (f64, f64)> {
+    let contents = fs::read_to_string(baseline_path(name)).unwrap_or_default();
+    contents.lines().filter_map(|l| {
+        let mut split = l.split_whitespace();
+        let name = split.next()?;
+        let mean = split.next()?.parse().ok()?;
+        let std_dev = split.next()?.parse().ok()?;
+        Some((name.to_string(), (mean, std_dev)))
+    }).collect()
+}
+
+// Benchmarks that were skipped this time keep their old numbers
+fn save_baseline(name: &str, bencher: &Bencher) -> std::io::Result<()> {
+    let mut baseline = bencher.baseline.clone();
+    for (bench, stats) in bencher.results.iter() {
+        baseline.insert(bench.clone(), (stats.mean, stats.std_dev));
+    }
+    let mut names: Vec<_> = baseline.keys().collect();
+    names.sort();
+    let contents: String = names.iter().map(|n| format!("{} {:.1} {:.1}\n", n, baseline[*n].0, baseline[*n].1)).collect();
+    let path = baseline_path(name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
+
+fn bench_shared(b: &mut Bencher) {
+    // The BOOST program in sensor boost mode runs a few hundred thousand instructions
+    if b.wants("intcode/run") {
+        match File::open(input_file(DEFAULT_YEAR, 9)).and_then(parse_program) {
+            Ok(program) => b.bench("intcode/run", || {
+                let mut machine = IntCodeMachine::new(&program, BufferedIO::new(&[2]));
+                machine.run();
+                machine
+            }),
+            Err(e) => eprintln!("Unable to read {}: {}", input_file(DEFAULT_YEAR, 9), e),
+        }
+    }
+
+    if b.wants("grid/from_reader") {
+        match fs::read(input_file(DEFAULT_YEAR, 18)) {
+            Ok(bytes) => b.bench("grid/from_reader", || Grid::<u8>::from_reader(&bytes[..])),
+            Err(e) => eprintln!("Unable to read {}: {}", input_file(DEFAULT_YEAR, 18), e),
+        }
+    }
+
+    if b.wants("day18/search") {
+        let grid = day18::Problem.parse_input(MAZE_EXAMPLE.as_bytes()).unwrap();
+        let maze = day18::MazeGraph::from_grid(&grid);
+        b.bench("day18/search", || maze.search(&vec!('@')));
+    }
+}
+
+// Negative, NaN or absurdly long durations are rejected rather than panicking later
+fn parse_seconds(s: &str) -> Option<Duration> {
+    s.parse().ok().and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+}
+
+pub fn run(args: &[String]) {
+    let mut year = DEFAULT_YEAR;
+    let mut filter = None;
+    let mut samples = 20;
+    let mut warm_up = Duration::from_secs(1);
+    let mut measure = Duration::from_secs(3);
+    let mut baseline = String::from("base");
+    let mut save = true;
+    let mut force_save = false;
+    let mut threshold = 5.0;
+    let mut sigmas = 2.0;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().ok_or(format!("Missing value for {}", name));
+        let parsed = match arg.as_str() {
+            "-h" | "--help" => { println!("{}", USAGE); return; },
+            "--year" => value("--year").and_then(|y| y.parse().map_err(|_| format!("Invalid year {}", y))).map(|y| year = y),
+            "--samples" => value("--samples").and_then(|s| s.parse().map_err(|_| format!("Invalid samples {}", s))).map(|s| samples = s),
+            "--warm-up" => value("--warm-up").and_then(|s| parse_seconds(s).ok_or(format!("Invalid warm-up {}", s))).map(|s| warm_up = s),
+            "--measure" => value("--measure").and_then(|s| parse_seconds(s).ok_or(format!("Invalid measure {}", s))).map(|s| measure = s),
+            "--baseline" => value("--baseline").map(|b| baseline = b.clone()),
+            "--threshold" => value("--threshold").and_then(|t| t.parse().map_err(|_| format!("Invalid threshold {}", t))).map(|t| threshold = t),
+            "--sigmas" => value("--sigmas").and_then(|k| k.parse().map_err(|_| format!("Invalid sigmas {}", k))).map(|k| sigmas = k),
+            "--no-save" => { save = false; Ok(()) },
+            "--save-baseline" => { force_save = true; Ok(()) },
+            a if a.starts_with('-') => Err(format!("Unknown option {}", a)),
+            a if filter.is_none() => { filter = Some(a.to_string()); Ok(()) },
+            a => Err(format!("Unexpected argument {}", a)),
+        };
+        if let Err(e) = parsed {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    }
+    if force_save && !save {
+        eprintln!("--no-save and --save-baseline can't be used together\n\n{}", USAGE);
+        process::exit(2);
+    }
+
+    let mut bencher = Bencher {
+        filter,
+        samples,
+        warm_up,
+        measure,
+        threshold,
+        sigmas,
+        baseline: load_baseline(&baseline),
+        results: vec!(),
+        regressions: 0,
+    };
+
+    bench_shared(&mut bencher);
+    for r in registry::for_year(year) {
+        if let Err(e) = r.solver.bench(r.day, year, &mut bencher) {
+            eprintln!("{}", e);
+        }
+    }
+
+    if bencher.results.is_empty() {
+        println!("No benchmarks matched");
+        return;
+    }
+    // A regressed run shouldn't become what the next one is compared with
+    if force_save || (save && bencher.regressions == 0) {
+        if let Err(e) = save_baseline(&baseline, &bencher) {
+            eprintln!("Unable to save the baseline {}: {}", baseline_path(&baseline).display(), e);
+        }
+    }
+    if bencher.regressions > 0 {
+        println!("{} benchmarks regressed by more than {}% and {} standard deviations", bencher.regressions, threshold, sigmas);
+        if save && !force_save {
+            println!("The baseline was kept as it was, use --save-baseline to replace it");
+        }
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_samples(vec!(9.0, 2.0, 5.0, 4.0, 4.0, 7.0, 4.0, 5.0));
+        assert_eq!((stats.mean, stats.median, stats.min, stats.max, stats.samples), (5.0, 4.5, 2.0, 9.0, 8));
+        // The sample standard deviation, sqrt(32 / 7)
+        assert!((stats.std_dev - 2.13809).abs() < 1e-5);
+
+        let stats = Stats::from_samples(vec!(3.0, 1.0, 2.0));
+        assert_eq!((stats.median, stats.std_dev), (2.0, 1.0));
+        assert_eq!(Stats::from_samples(vec!(4.0)).std_dev, 0.0);
+    }
+
+    #[test]
+    fn regressions() {
+        // 10% slower, past a 5% threshold and two standard deviations of 2
+        assert!(regressed(110.0, (100.0, 2.0), 5.0, 2.0));
+        // Past the threshold but within the spread of a noisy benchmark
+        assert!(!regressed(110.0, (100.0, 6.0), 5.0, 2.0));
+        // Past the spread of a steady benchmark but within the threshold
+        assert!(!regressed(103.0, (100.0, 0.5), 5.0, 2.0));
+        assert!(!regressed(90.0, (100.0, 0.0), 5.0, 2.0));
+    }
+
+    #[test]
+    fn seconds() {
+        assert_eq!(parse_seconds("0.5"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_seconds("0"), Some(Duration::ZERO));
+        for invalid in ["-1", "NaN", "inf", "1e30", "soon"] {
+            assert_eq!(parse_seconds(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn baseline_paths() {
+        assert_eq!(baseline_path("base"), Path::new(env!("CARGO_MANIFEST_DIR")).join("target/bench/base.txt"));
+        assert_eq!(baseline_path("/tmp/base.txt"), PathBuf::from("/tmp/base.txt"));
+    }
+}
@@ -0,0 +1,297 @@
+use crate::{
+    answers,
+    registry,
+    solver::{input_root, input_set_dir, InputSource, Options, DEFAULT_YEAR},
+    visualize::{ImageFormat, Target},
+};
+
+pub const USAGE: &str = "\
+Usage: aoc_2019 [DAYS] [OPTIONS]
+       aoc_2019 fuzz|memview|serve|bench|new|menu [ARGS]
+
+DAYS is a day (5), a list (1,3,7), a range (10-15), a mix of those (1,3-5) or
+\"all\", day 1 is run if no days are given. Without any arguments at a terminal
+a menu to pick the days from is shown instead.
+
+Options:
+  -y, --year <YEAR>    run the days of YEAR, 2019 by default, inputs of other
+                       years are read from input/YEAR/dayNN.txt
+  -l, --list           list the solutions that are available, of every year
+                       unless --year is given
+      --tag <TAG>      only list or run the solutions tagged with TAG, e.g.
+                       intcode or grid, all of them if no days are given
+  -p, --part <1|2>     only run one part of each day
+  -i, --input <PATH>   read the input from PATH instead of input/dayNN.txt,
+                       \"-\" reads it from stdin
+      --input-dir <DIR>
+                       read the inputs from DIR instead of input/, the
+                       AOC_INPUT_DIR environment variable does the same
+      --input-set <NAMES>
+                       run the days once for each comma separated input set,
+                       e.g. alice,bob reads input/alice/ then input/bob/,
+                       each with its own answers.txt in there
+  -j, --jobs <N>       run up to N days at once and both parts of each day at
+                       the same time, 0 runs as many as there are cores,
+                       answers are still printed in order but anything the
+                       days print while solving may be mixed together
+  -q, --quiet          only print the answers
+      --jsonl          print a JSON object per line for each part instead,
+                       with the year, day, part, answer, timings, heap usage
+                       with --memory and error, anything else that is
+                       reported goes to stderr
+  -t, --timings        time parsing and each part, print a table at the end
+                       and show progress of slow days on stderr
+  -m, --memory         measure the peak heap usage and number of allocations
+                       of parsing and each part, print a table at the end
+      --visualize <term|DIR>
+                       show what the days that draw themselves are doing
+                       while they solve, animated on the terminal with
+                       \"term\", otherwise save each frame as an image in DIR
+      --frame-format <png|ppm>
+                       the kind of image --visualize saves, png by default
+      --timings-out <PATH>
+                       also write the timings to PATH, as CSV if it ends
+                       with .csv and JSON otherwise
+  -w, --watch          run the days again with timings whenever their input or
+                       the sources change, rebuilding first for the sources,
+                       and show how the answers changed since the last run,
+                       until q is entered
+  -v, --verify         compare the answers with the expected answers and exit
+                       with an error if any of them differ or are missing
+      --allow-missing  let --verify pass for answers without an expected one
+      --answers <PATH> file with the expected answers, answers.txt by default
+                       and answers-YEAR.txt for other years, next to the
+                       input directory or in it for an input set
+      --record-answers store the answers as the expected answers
+  -h, --help           print this message";
+
+// One go at the days, with its own inputs and expected answers
+pub struct InputSet {
+    pub name: Option<String>,
+    pub options: Options,
+    pub answers_file: String,
+}
+
+pub struct Args {
+    pub days: Vec<i32>,
+    // Those of the first input set, the others only differ in their inputs
+    pub options: Options,
+    pub input_sets: Vec<InputSet>,
+    pub jobs: usize,
+    pub jsonl: bool,
+    pub watch: bool,
+    pub timings: bool,
+    pub timings_out: Option<String>,
+    pub verify: bool,
+    pub allow_missing: bool,
+    pub record_answers: bool,
+    // Only set when given, the options always have a year to run
+    pub year: Option<i32>,
+    pub list: bool,
+    pub tag: Option<String>,
+    pub help: bool,
+}
+
+pub fn parse(args: &[String]) -> Result<Args, String> {
+    let mut days = None;
+    let mut options = Options::default();
+    let mut jobs = 1;
+    let mut jsonl = false;
+    let mut watch = false;
+    let mut timings = false;
+    let mut timings_out = None;
+    let mut verify = false;
+    let mut allow_missing = false;
+    let mut record_answers = false;
+    let mut answers_file = None;
+    let mut input_dir = None;
+    let mut set_names = vec!();
+    let mut frame_format = None;
+    let mut year = None;
+    let mut list = false;
+    let mut tag = None;
+    let mut help = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => help = true,
+            "-q" | "--quiet" => options.quiet = true,
+            "-y" | "--year" => {
+                let y = iter.next().ok_or("Missing value for --year")?;
+                year = Some(y.parse::<i32>().map_err(|_| format!("Invalid year {}", y))?);
+            },
+            "-l" | "--list" => list = true,
+            "--tag" => tag = Some(iter.next().ok_or("Missing value for --tag")?.clone()),
+            "-j" | "--jobs" => {
+                let j = iter.next().ok_or("Missing value for --jobs")?;
+                jobs = j.parse().map_err(|_| format!("Invalid number of jobs {}", j))?;
+            },
+            "--jsonl" => jsonl = true,
+            "-w" | "--watch" => watch = true,
+            "-t" | "--timings" => timings = true,
+            "-m" | "--memory" => options.memory = true,
+            "--visualize" => {
+                let target = iter.next().ok_or("Missing value for --visualize")?;
+                options.visualize = Some(match target.as_str() {
+                    "term" => Target::Terminal,
+                    dir => Target::Images { dir: dir.to_string(), format: ImageFormat::Png },
+                });
+            },
+            "--frame-format" => {
+                let format = iter.next().ok_or("Missing value for --frame-format")?;
+                frame_format = Some(match format.as_str() {
+                    "png" => ImageFormat::Png,
+                    "ppm" => ImageFormat::Ppm,
+                    f => return Err(format!("Invalid frame format {}, expected png or ppm", f)),
+                });
+            },
+            "--timings-out" => {
+                timings = true;
+                timings_out = Some(iter.next().ok_or("Missing value for --timings-out")?.clone());
+            },
+            "-v" | "--verify" => verify = true,
+            "--allow-missing" => allow_missing = true,
+            "--record-answers" => record_answers = true,
+            "--answers" => answers_file = Some(iter.next().ok_or("Missing value for --answers")?.clone()),
+            "-p" | "--part" => {
+                let part = iter.next().ok_or("Missing value for --part")?;
+                options.part = match part.as_str() {
+                    "1" => Some(1),
+                    "2" => Some(2),
+                    p => return Err(format!("Invalid part {}, expected 1 or 2", p)),
+                };
+            },
+            "--input-dir" => input_dir = Some(iter.next().ok_or("Missing value for --input-dir")?.clone()),
+            "--input-set" => {
+                let names = iter.next().ok_or("Missing value for --input-set")?;
+                set_names = names.split(',').map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).collect();
+            },
+            "-i" | "--input" => {
+                let path = iter.next().ok_or("Missing value for --input")?;
+                options.input = match path.as_str() {
+                    "-" => InputSource::Stdin,
+                    p => InputSource::Path(p.to_string()),
+                };
+            },
+            a if a.starts_with('-') => return Err(format!("Unknown option {}", a)),
+            a => {
+                if days.is_some() {
+                    return Err(format!("Unexpected argument {}", a));
+                }
+                days = Some(a);
+            }
+        }
+    }
+
+    options.year = year.unwrap_or(DEFAULT_YEAR);
+    let days = match (days.map(|d| parse_days(d, options.year)).transpose()?, &tag) {
+        (Some(days), Some(tag)) => days.into_iter().filter(|&d| registry::find(options.year, d).is_some_and(|r| r.has_tag(tag))).collect(),
+        (None, Some(tag)) => registry::with_tag(options.year, tag).iter().map(|r| r.day).collect(),
+        (Some(days), None) => days,
+        (None, None) => vec!(1),
+    };
+    if days.is_empty() && !list {
+        return Err(format!("No days to run with the tag {}", tag.unwrap_or_default()));
+    }
+    let custom_input = !matches!(options.input, InputSource::Dir(_));
+    if days.len() > 1 && custom_input {
+        return Err(String::from("A custom input can only be used with a single day"));
+    }
+    if custom_input && (input_dir.is_some() || !set_names.is_empty()) {
+        return Err(String::from("A custom input can't be used with an input directory or set"));
+    }
+    // Every thread shares the same counters
+    if options.memory && jobs != 1 {
+        return Err(String::from("--memory needs the days to run one at a time, without --jobs"));
+    }
+    // Only one stage can be drawn at a time
+    if options.visualize.is_some() && jobs != 1 {
+        return Err(String::from("--visualize needs the days to run one at a time, without --jobs"));
+    }
+    match (&mut options.visualize, frame_format) {
+        (Some(Target::Images { format, .. }), Some(f)) => *format = f,
+        (_, Some(_)) => return Err(String::from("--frame-format only works with --visualize DIR")),
+        _ => (),
+    }
+    if allow_missing && !verify {
+        return Err(String::from("--allow-missing only works with --verify"));
+    }
+    if set_names.len() > 1 && (answers_file.is_some() || timings_out.is_some() || watch) {
+        return Err(String::from("--answers, --timings-out and --watch only work with a single input set"));
+    }
+
+    // Several spinners would fight over the same line, one would be counted by --memory
+    // and the terminal animation already shows what is going on
+    options.progress = timings && jobs == 1 && !options.memory && options.visualize != Some(Target::Terminal);
+    options.parallel = jobs != 1;
+
+    let root = input_dir.unwrap_or_else(input_root);
+    let input_sets: Vec<InputSet> = if set_names.is_empty() {
+        if !custom_input {
+            options.input = InputSource::Dir(root.clone());
+        }
+        let answers_file = answers_file.unwrap_or_else(|| answers::default_file(&root, options.year));
+        vec!(InputSet { name: None, options: options.clone(), answers_file })
+    } else {
+        set_names.into_iter().map(|name| {
+            let dir = input_set_dir(&root, &name);
+            let answers_file = answers_file.clone().unwrap_or_else(|| answers::set_file(&dir, options.year));
+            let mut options = Options { input: InputSource::Dir(dir), ..options.clone() };
+            // The frames of each set in their own directory so they don't overwrite each other
+            if let Some(Target::Images { dir, .. }) = &mut options.visualize {
+                *dir = format!("{}/{}", dir, name);
+            }
+            InputSet { name: Some(name), options, answers_file }
+        }).collect()
+    };
+    let options = input_sets[0].options.clone();
+
+    Ok(Args { days, options, input_sets, jobs, jsonl, watch, timings, timings_out, verify, allow_missing, record_answers, year, list, tag, help })
+}
+
+fn parse_days(s: &str, year: i32) -> Result<Vec<i32>, String> {
+    if s == "all" {
+        let days: Vec<i32> = registry::for_year(year).iter().map(|r| r.day).collect();
+        if days.is_empty() {
+            return Err(format!("No days registered for {}", year));
+        }
+        return Ok(days);
+    }
+
+    let parse_day = |d: &str| match d.trim().parse::<i32>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        Ok(day) => Err(format!("Invalid day {}, expected 1 to 25", day)),
+        Err(_) => Err(format!("Invalid day {}", d)),
+    };
+    let mut days = vec!();
+    for part in s.split(',') {
+        match part.find('-') {
+            Some(i) => {
+                let (start, end) = (parse_day(&part[..i])?, parse_day(&part[i + 1..])?);
+                if start > end {
+                    return Err(format!("Invalid range {}", part));
+                }
+                days.extend(start..=end);
+            },
+            None => days.push(parse_day(part)?),
+        }
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_and_ranges() {
+        assert_eq!(parse_days("3,7-9", DEFAULT_YEAR), Ok(vec!(3, 7, 8, 9)));
+        assert_eq!(parse_days("all", DEFAULT_YEAR).map(|d| d.len()), Ok(25));
+        assert_eq!(parse_days("9-7", DEFAULT_YEAR), Err(String::from("Invalid range 9-7")));
+        assert_eq!(parse_days("24-26", DEFAULT_YEAR), Err(String::from("Invalid day 26, expected 1 to 25")));
+        assert_eq!(parse_days("0", DEFAULT_YEAR), Err(String::from("Invalid day 0, expected 1 to 25")));
+        assert_eq!(parse_days("x", DEFAULT_YEAR), Err(String::from("Invalid day x")));
+        assert_eq!(parse_days("all", 2015), Err(String::from("No days registered for 2015")));
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{
+    intcode::{parse_program, BufferedIO, IntCodeMachine},
+    solver::{input_file, DEFAULT_YEAR},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    panic,
+    process,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const USAGE: &str = "Usage: fuzz <day> [iterations] [cycle budget] [ascii] [--seed <SEED>]";
+
+const DEFAULT_ITERATIONS: usize = 10000;
+const DEFAULT_CYCLE_BUDGET: usize = 1000000;
+const MAX_INPUT_LEN: usize = 256;
+const REPORT_SIZE: usize = 10;
+const REPORT_OUTPUT_TAIL: usize = 200;
+
+// Small xorshift generator, good enough for picking mutations
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed | 1 }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[derive(Clone)]
+pub struct CorpusEntry {
+    pub input: Vec<i64>,
+    pub output: Vec<i64>,
+    pub covered: HashSet<usize>,
+    // Number of instructions this input was the first to reach
+    pub new_pcs: usize,
+    pub crashed: bool,
+}
+
+pub struct Fuzzer {
+    program: Vec<i64>,
+    cycle_budget: usize,
+    alphabet: Vec<i64>,
+    seed: u64,
+    rng: Rng,
+    corpus: Vec<CorpusEntry>,
+    // How many runs reached each pc, used to rank the rarity of an input
+    hits: HashMap<usize, usize>,
+    runs: usize,
+}
+
+impl Fuzzer {
+    pub fn new(program: &[i64], cycle_budget: usize) -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64);
+        Self {
+            program: program.to_vec(),
+            cycle_budget,
+            alphabet: (-2..=10).collect(),
+            seed,
+            rng: Rng::new(seed),
+            corpus: vec!(),
+            hits: HashMap::new(),
+            runs: 0,
+        }
+    }
+
+    // Values that mutations will pick from, e.g. printable characters for the ASCII programs
+    pub fn with_alphabet(mut self, alphabet: Vec<i64>) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    // Picks the mutations the same way as an earlier run with this seed, to reproduce what it found
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn add_seed(&mut self, input: Vec<i64>) {
+        self.execute(input);
+    }
+
+    pub fn fuzz(&mut self, iterations: usize) {
+        if self.corpus.is_empty() {
+            self.add_seed(vec!());
+        }
+        for _ in 0..iterations {
+            let parent = self.rng.below(self.corpus.len());
+            let candidate = self.mutate(&self.corpus[parent].input.clone());
+            self.execute(candidate);
+        }
+    }
+
+    pub fn coverage(&self) -> usize {
+        self.hits.len()
+    }
+
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    pub fn corpus(&self) -> &Vec<CorpusEntry> {
+        &self.corpus
+    }
+
+    // Inputs ranked by how many instructions they discovered, then by how rarely
+    // the rest of the corpus reaches the instructions they cover
+    pub fn most_novel(&self, n: usize) -> Vec<&CorpusEntry> {
+        let rarity = |e: &CorpusEntry| -> f64 {
+            e.covered.iter().map(|pc| 1.0 / *self.hits.get(pc).unwrap_or(&1) as f64).sum()
+        };
+        let mut ranked: Vec<_> = self.corpus.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.new_pcs.cmp(&a.new_pcs).then(rarity(b).partial_cmp(&rarity(a)).unwrap())
+        });
+        ranked.truncate(n);
+        ranked
+    }
+
+    fn mutate(&mut self, input: &[i64]) -> Vec<i64> {
+        let mut output = input.to_vec();
+        let rounds = 1 + self.rng.below(4);
+        for _ in 0..rounds {
+            let value = self.alphabet[self.rng.below(self.alphabet.len())];
+            match self.rng.below(5) {
+                // Replace a value
+                0 if !output.is_empty() => {
+                    let i = self.rng.below(output.len());
+                    output[i] = value;
+                },
+                // Remove a value
+                1 if !output.is_empty() => {
+                    let i = self.rng.below(output.len());
+                    output.remove(i);
+                },
+                // Splice in the tail of another corpus entry
+                2 => {
+                    let other = &self.corpus[self.rng.below(self.corpus.len())].input;
+                    if !other.is_empty() {
+                        let from = self.rng.below(other.len());
+                        let to = self.rng.below(output.len() + 1);
+                        output.truncate(to);
+                        output.extend_from_slice(&other[from..]);
+                    }
+                },
+                // Insert a value somewhere
+                3 => {
+                    let i = self.rng.below(output.len() + 1);
+                    output.insert(i, value);
+                },
+                // Append a value
+                _ => output.push(value),
+            }
+        }
+        output.truncate(MAX_INPUT_LEN);
+        output
+    }
+
+    fn execute(&mut self, input: Vec<i64>) {
+        self.runs += 1;
+        let covered = Arc::new(Mutex::new(HashSet::new()));
+        let mut machine = IntCodeMachine::new(&self.program, BufferedIO::new(&input));
+        let tracer_covered = covered.clone();
+        machine.set_tracer(Box::new(move |pc, _| { tracer_covered.lock().unwrap().insert(pc); }));
+
+        // Malformed paths through a program can hit invalid opcodes or addresses, treat them as crashes
+        let budget = self.cycle_budget;
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            machine.run_for(budget);
+            machine.io.output.clone()
+        }));
+        let crashed = result.is_err();
+        let output = result.unwrap_or_default();
+        let covered = covered.lock().unwrap().clone();
+
+        let mut new_pcs = 0;
+        for pc in covered.iter() {
+            let count = self.hits.entry(*pc).or_insert(0);
+            if *count == 0 {
+                new_pcs += 1;
+            }
+            *count += 1;
+        }
+
+        if new_pcs > 0 {
+            self.corpus.push(CorpusEntry { input, output, covered, new_pcs, crashed });
+        }
+    }
+}
+
+fn format_values(values: &[i64]) -> String {
+    let printable = values.iter().all(|v| (32..127).contains(v) || *v == 10);
+    if printable && !values.is_empty() {
+        values.iter().map(|v| *v as u8 as char).collect::<String>().escape_default().to_string()
+    } else {
+        format!("{:?}", values)
+    }
+}
+
+struct Args {
+    day: i32,
+    iterations: usize,
+    cycle_budget: usize,
+    ascii: bool,
+    seed: Option<u64>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut positional = vec!();
+    let mut ascii = false;
+    let mut seed = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = iter.next().ok_or("Missing value for --seed")?;
+                seed = Some(value.parse().map_err(|_| format!("Invalid seed {}", value))?);
+            },
+            "ascii" => ascii = true,
+            a if a.starts_with('-') && a.parse::<i64>().is_err() => return Err(format!("Unknown option {}", a)),
+            a => positional.push(a),
+        }
+    }
+
+    let day = positional.first().ok_or("Missing day")?;
+    let day = day.parse().map_err(|_| format!("Invalid day {}", day))?;
+    let number = |i: usize, name: &str, default: usize| -> Result<usize, String> {
+        positional.get(i).map_or(Ok(default), |v| v.parse().map_err(|_| format!("Invalid {} {}", name, v)))
+    };
+    let iterations = number(1, "iterations", DEFAULT_ITERATIONS)?;
+    let cycle_budget = number(2, "cycle budget", DEFAULT_CYCLE_BUDGET)?;
+    if let Some(extra) = positional.get(3) {
+        return Err(format!("Unexpected argument {}", extra));
+    }
+    Ok(Args { day, iterations, cycle_budget, ascii, seed })
+}
+
+pub fn run(args: &[String]) {
+    let Args { day, iterations, cycle_budget, ascii, seed } = match parse_args(args) {
+        Ok(a) => a,
+        Err(e) => { eprintln!("{}\n\n{}", e, USAGE); process::exit(2); }
+    };
+
+    let program = match File::open(input_file(DEFAULT_YEAR, day)).and_then(parse_program) {
+        Ok(p) => p,
+        Err(e) => { eprintln!("Unable to read {}: {}", input_file(DEFAULT_YEAR, day), e); process::exit(1); }
+    };
+
+    let mut fuzzer = Fuzzer::new(&program, cycle_budget);
+    if let Some(seed) = seed {
+        fuzzer = fuzzer.with_seed(seed);
+    }
+    if ascii {
+        fuzzer = fuzzer.with_alphabet((32..127).chain(vec!(10)).collect());
+    }
+    println!("Fuzzing day {} with seed {}, pass --seed {} to repeat this run", day, fuzzer.seed(), fuzzer.seed());
+
+    // Don't spam the terminal with every crash that we catch
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    fuzzer.fuzz(iterations);
+    panic::set_hook(default_hook);
+
+    println!("Ran {} inputs, covered {} instructions, corpus has {} entries",
+             fuzzer.runs(), fuzzer.coverage(), fuzzer.corpus().len());
+    for (i, entry) in fuzzer.most_novel(REPORT_SIZE).iter().enumerate() {
+        println!("#{} new: {}, covered: {}{}", i + 1, entry.new_pcs, entry.covered.len(),
+                 if entry.crashed { ", crashed" } else { "" });
+        println!("    input:  {}", format_values(&entry.input));
+        let tail = &entry.output[entry.output.len().saturating_sub(REPORT_OUTPUT_TAIL)..];
+        println!("    output: {}", format_values(tail));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads a value and outputs it unless it is 0
+    const BRANCH: [i64; 9] = [3, 20, 1005, 20, 6, 99, 4, 20, 99];
+
+    #[test]
+    fn rng_is_deterministic() {
+        let sample = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next()).collect::<Vec<_>>()
+        };
+        assert_eq!(sample(42), sample(42));
+        assert_ne!(sample(42), sample(44));
+    }
+
+    #[test]
+    fn corpus_grows_on_new_coverage() {
+        let mut fuzzer = Fuzzer::new(&BRANCH, 100);
+        fuzzer.add_seed(vec!(0));
+        assert_eq!(fuzzer.corpus().len(), 1);
+        assert_eq!(fuzzer.coverage(), 3);
+        // Takes the same path, nothing new
+        fuzzer.add_seed(vec!(0));
+        assert_eq!(fuzzer.corpus().len(), 1);
+
+        fuzzer.add_seed(vec!(5));
+        assert_eq!(fuzzer.corpus().len(), 2);
+        let entry = &fuzzer.corpus()[1];
+        assert_eq!((entry.new_pcs, entry.output.clone(), entry.crashed), (2, vec!(5), false));
+        assert_eq!((fuzzer.runs(), fuzzer.coverage()), (3, 5));
+    }
+
+    #[test]
+    fn detects_crashes() {
+        // Writes far past the memory limit, then an invalid opcode
+        for program in [vec!(1101, 0, 0, 1000000000000, 99), vec!(42)] {
+            let mut fuzzer = Fuzzer::new(&program, 100);
+            fuzzer.add_seed(vec!());
+            assert!(fuzzer.corpus()[0].crashed);
+        }
+    }
+
+    #[test]
+    fn seed_repeats_a_run() {
+        let inputs = |seed| {
+            let mut fuzzer = Fuzzer::new(&BRANCH, 100).with_seed(seed);
+            fuzzer.fuzz(50);
+            fuzzer.corpus().iter().map(|e| e.input.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(inputs(7), inputs(7));
+    }
+
+    #[test]
+    fn parses_args() {
+        let args = |s: &str| parse_args(&s.split_whitespace().map(String::from).collect::<Vec<_>>());
+        let parsed = args("25 100 --seed 9 ascii").unwrap();
+        assert_eq!((parsed.day, parsed.iterations, parsed.cycle_budget, parsed.ascii, parsed.seed), (25, 100, DEFAULT_CYCLE_BUDGET, true, Some(9)));
+        assert_eq!(args("").err(), Some(String::from("Missing day")));
+        assert_eq!(args("25 --seed").err(), Some(String::from("Missing value for --seed")));
+        assert_eq!(args("25 lots").err(), Some(String::from("Invalid iterations lots")));
+    }
+}
@@ -1,3 +1,13 @@
+//! Grids of cells read from the puzzle inputs, and points and directions on them.
+//!
+//! ```
+//! use aoc_2019::grid::Grid;
+//!
+//! let grid: Grid<u8> = Grid::from_reader("#.\n.#".as_bytes()).unwrap();
+//! assert_eq!((grid.w, grid.h), (2, 2));
+//! assert_eq!(grid.get((1, 1)), Some(&b'#'));
+//! ```
+
 use std::convert::TryFrom;
 use std::{
     io::{BufRead, BufReader, Read},
@@ -6,10 +16,13 @@ use std::{
 };
 use num::integer::gcd;
 
+/// A rectangle of cells, stored row by row.
 #[derive(Clone)]
 pub struct Grid<T> {
     cells: Vec<T>,
+    /// The width.
     pub w: usize,
+    /// The height.
     pub h: usize,
 }
 
@@ -17,10 +30,12 @@ impl<T> Grid<T>
 where
     T: Clone + Default + TryFrom<u8>,
 {
+    /// A grid of default cells.
     pub fn new(w: usize, h: usize) -> Self {
         Self::new_with(w, h, Default::default())
     }
 
+    /// A grid with every cell set to `val`.
     pub fn new_with(w: usize, h: usize, val: T) -> Self {
         Self {
             cells: Vec::from_iter(repeat(val).take(w * h)),
@@ -29,6 +44,8 @@ where
         }
     }
 
+    /// Reads a cell from each byte, a row from each line. The width is that of
+    /// the first line.
     pub fn from_reader<R: Read>(r: R) -> Result<Self, T::Error> {
         let cells = BufReader::new(r)
             .lines()
@@ -45,42 +62,66 @@ where
         })
     }
 
+    /// Sets the cell at `c`, does nothing if it is outside the grid.
     pub fn set(&mut self, c: impl Coord, value: T) {
-        if let Some(e) = self.cells.get_mut(c.x() + c.y() * self.w) {
+        if let Some(e) = self.index(c).and_then(|i| self.cells.get_mut(i)) {
             *e = value;
         }
     }
 
+    /// The cell at `c`, if it is inside the grid.
     pub fn get(&self, c: impl Coord) -> Option<&T> {
-        self.cells.get(c.x() + c.y() * self.w)
+        self.index(c).and_then(|i| self.cells.get(i))
+    }
+
+    // A column past the width would otherwise wrap into the next row
+    fn index(&self, c: impl Coord) -> Option<usize> {
+        let (x, y) = c.coords();
+        if x < self.w && y < self.h {
+            Some(x + y * self.w)
+        } else {
+            None
+        }
     }
 }
 
+/// A position in a [`Grid`], counted from the top left.
 pub trait Coord {
+    /// The column.
     fn x(&self) -> usize;
+    /// The row.
     fn y(&self) -> usize;
+    /// Both at once.
     fn coords(&self) -> (usize, usize) {
         (self.x(), self.y())
     }
 }
 
+/// A point on an unbounded plane, e.g. where a robot has got to.
 #[derive(Clone, Eq, PartialEq, Hash, Copy, Debug)]
 pub struct Point {
+    /// Grows to the right.
     pub x: i64,
+    /// Grows downwards.
     pub y: i64,
 }
 
+/// A cell of a [`Grid`].
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct GridPoint {
+    /// The column.
     pub x: usize,
+    /// The row.
     pub y: usize,
 }
 
 impl GridPoint {
+    /// The Manhattan distance between the two.
     pub fn distance(&self, other: &Self) -> usize {
         ((self.x as isize - other.x as isize).abs() + (self.y as isize - other.y as isize).abs()) as usize
     }
 
+    /// The direction from this point to `other`.
     pub fn vector(&self, other: &Self) -> Vector2D {
         Vector2D::new(
             other.x as isize - self.x as isize,
@@ -113,6 +154,8 @@ impl Coord for (usize, usize) {
     }
 }
 
+/// A direction, kept as the smallest whole step in it so that vectors pointing
+/// the same way compare equal.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct Vector2D {
     dx: isize,
@@ -120,11 +163,13 @@ pub struct Vector2D {
 }
 
 impl Vector2D {
+    /// The direction of `(dx, dy)`, which mustn't both be 0.
     pub fn new(dx: isize, dy: isize) -> Self {
         let gcd = gcd(dx, dy);
         Self { dx: dx / gcd, dy: dy / gcd }
     }
 
+    /// The clockwise angle from straight up, from 0 up to 360.
     pub fn degrees(&self) -> f64 {
         let angle = (self.dy as f64).atan2(self.dx as f64); // returns in terms of radians
         let d = 180.0 * angle / PI + 90.0; // calculate degrees, then add 90.0 as origin is up instead of right
@@ -135,3 +180,23 @@ impl Vector2D {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_inside() {
+        let mut grid: Grid<u8> = Grid::from_reader("ab\ncd".as_bytes()).unwrap();
+        assert_eq!(grid.get((1, 0)), Some(&b'b'));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.get((usize::MAX, 1)), None);
+
+        grid.set((2, 0), b'x');
+        grid.set((1, 2), b'x');
+        grid.set((1, 1), b'x');
+        assert_eq!(grid.get((0, 1)), Some(&b'c'));
+        assert_eq!(grid.get((1, 1)), Some(&b'x'));
+    }
+}
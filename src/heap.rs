@@ -0,0 +1,168 @@
+//! Measuring the heap usage of the stages of the days.
+
+use crate::solver::{DayResult, SolveError};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering},
+};
+
+/// The system allocator, counting what goes through it while tracking is on.
+/// The counters are shared by every thread, so a stage is only measured
+/// properly when nothing else runs at the same time.
+pub struct CountingAllocator;
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+// Only what changed while tracking, freeing memory from before it started makes this go down
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+fn grown(size: usize) {
+    let current = CURRENT.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+    ALLOCATED.fetch_add(size, Ordering::Relaxed);
+}
+
+fn shrunk(size: usize) {
+    CURRENT.fetch_sub(size as isize, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && TRACKING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            grown(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && TRACKING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            grown(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if TRACKING.load(Ordering::Relaxed) {
+            shrunk(layout.size());
+        }
+    }
+
+    // A growing Vec or HashMap is counted as an allocation each time it moves
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && TRACKING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            if new_size > layout.size() {
+                grown(new_size - layout.size());
+            } else {
+                shrunk(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// What a stage did with the heap.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Usage {
+    /// Most bytes in use at once on top of what was there when the stage started.
+    pub peak: usize,
+    /// How many times memory was allocated or reallocated.
+    pub allocations: usize,
+    /// Every byte asked for, however briefly.
+    pub allocated: usize,
+}
+
+/// Runs f and measures its heap usage, when enabled. Only works with
+/// [`CountingAllocator`] as the global allocator, otherwise it all comes out as 0.
+pub fn measure<T>(enabled: bool, f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+    if !enabled {
+        return (f(), None);
+    }
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+    TRACKING.store(true, Ordering::SeqCst);
+
+    let result = f();
+
+    TRACKING.store(false, Ordering::SeqCst);
+    let usage = Usage {
+        peak: (PEAK.load(Ordering::Relaxed) - start).max(0) as usize,
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+    };
+    (result, Some(usage))
+}
+
+/// Bytes in the largest unit that keeps them above 1, e.g. 1.50KiB.
+pub fn format_bytes(bytes: usize) -> String {
+    match bytes as f64 {
+        b if b >= 1024.0 * 1024.0 * 1024.0 => format!("{:.2}GiB", b / (1024.0 * 1024.0 * 1024.0)),
+        b if b >= 1024.0 * 1024.0 => format!("{:.2}MiB", b / (1024.0 * 1024.0)),
+        b if b >= 1024.0 => format!("{:.2}KiB", b / 1024.0),
+        _ => format!("{}B", bytes),
+    }
+}
+
+fn format_usage(usage: Option<Usage>) -> (String, String) {
+    match usage {
+        Some(u) => (format_bytes(u.peak), u.allocations.to_string()),
+        None => (String::from("-"), String::from("-")),
+    }
+}
+
+/// The peak and number of allocations of each stage, like [`timing::table`](crate::timing::table).
+pub fn table(results: &[(i32, Result<DayResult, SolveError>)]) -> String {
+    let mut output = format!("{:>5} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n",
+                             "Day", "Parse", "allocs", "Part 1", "allocs", "Part 2", "allocs");
+    for (day, result) in results {
+        match result {
+            Ok(r) => {
+                let parse = format_usage(r.parse_memory);
+                let first = format_usage(r.first.as_ref().and_then(|a| a.memory));
+                let second = format_usage(r.second.as_ref().and_then(|a| a.memory));
+                output.push_str(&format!("{:>5} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n", day,
+                                         parse.0, parse.1, first.0, first.1, second.0, second.1));
+            },
+            Err(e) => output.push_str(&format!("{:>5} failed: {}\n", day, e)),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    // Other tests allocate and free at the same time, so only check for at least what was asked for
+    #[test]
+    fn measures_allocations() {
+        let (v, usage) = measure(true, || black_box(vec!(0u8; 1 << 20)));
+        let usage = usage.unwrap();
+        assert!(usage.allocated >= v.len());
+        assert!(usage.allocations >= 1);
+
+        let (_, usage) = measure(false, || black_box(vec!(0u8; 16)));
+        assert_eq!(usage, None);
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.50KiB");
+        assert_eq!(format_bytes(10 << 20), "10.00MiB");
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{registry, runner::DayOutcome, solver::{beside_root, input_root}};
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+const HISTORY_FILE: &str = "target/last-run.txt";
+
+// In the target/ next to the inputs, wherever it is run from
+fn history_file() -> String {
+    beside_root(&input_root(), HISTORY_FILE)
+}
+
+// How long each day took the last time it ran all the way through, by year and day
+pub fn load() -> HashMap<(i32, i32), Duration> {
+    let contents = fs::read_to_string(history_file()).unwrap_or_default();
+    contents.lines().filter_map(|l| {
+        let mut split = l.split_whitespace();
+        let year = split.next()?.parse().ok()?;
+        let day = split.next()?.parse().ok()?;
+        let millis: f64 = split.next()?.parse().ok()?;
+        Some(((year, day), Duration::from_secs_f64(millis / 1000.0)))
+    }).collect()
+}
+
+// One day per line as "<year> <day> <total ms>", only days that ran all of their parts are kept
+pub fn record(year: i32, results: &[DayOutcome]) {
+    let mut history = load();
+    let mut changed = false;
+    for (day, result) in results {
+        if let Ok(r) = result {
+            let second_part = registry::find(year, *day).is_none_or(|reg| reg.solver.second_part());
+            if r.first.is_some() && (r.second.is_some() || !second_part) {
+                history.insert((year, *day), r.total_time());
+                changed = true;
+            }
+        }
+    }
+    if !changed {
+        return;
+    }
+
+    let mut days: Vec<_> = history.into_iter().collect();
+    days.sort_by_key(|(k, _)| *k);
+    let contents: String = days.iter()
+        .map(|((year, day), time)| format!("{} {} {:.3}\n", year, day, time.as_secs_f64() * 1000.0))
+        .collect();
+    // Only kept when run from the repository, where there is a target/ to keep it in
+    let path = history_file();
+    if Path::new(&path).parent().is_some_and(|d| d.is_dir()) {
+        let _ = fs::write(&path, contents);
+    }
+}
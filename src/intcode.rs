@@ -1,19 +1,37 @@
+//! The Intcode computer shared by the odd days from day 2 onwards.
+//!
+//! A machine runs a program against an [`IO`], which decides where its inputs
+//! come from and where its outputs go:
+//!
+//! ```
+//! use aoc_2019::intcode::{parse_program, BufferedIO, IntCodeMachine};
+//!
+//! // Outputs the sum of its two inputs
+//! let program = parse_program("3,11,3,12,1,11,12,13,4,13,99".as_bytes()).unwrap();
+//! let mut machine = IntCodeMachine::new(&program, BufferedIO::new(&[2, 3]));
+//! machine.run();
+//! assert_eq!(machine.io.output, vec!(5));
+//! ```
+
 use std::convert::TryInto;
 use std::{
-    fs::File,
-    io::{BufRead, BufReader, ErrorKind, Result},
+    collections::VecDeque,
+    io::{BufRead, BufReader, ErrorKind, Read, Result},
     sync::mpsc::{channel, Receiver, Sender}
 };
 use std::io;
 
-pub fn parse_program(f: File) -> Vec<i64> {
-    BufReader::new(f)
+/// How many values the memory of a machine can grow to, an address at or past
+/// this makes the machine panic rather than try to allocate it.
+pub const MEMORY_LIMIT: usize = 1 << 20;
+
+/// Reads a comma separated program, which may be split over several lines.
+pub fn parse_program<R: Read>(r: R) -> Result<Vec<i64>> {
+    BufReader::new(r)
         .lines()
-        .flatten()
-        .collect::<String>()
+        .collect::<Result<String>>()?
         .split(",")
-        .map(String::from)
-        .map(|s| s.parse().unwrap())
+        .map(|s| s.parse().map_err(|e| io::Error::new(ErrorKind::InvalidData, format!("Invalid value {:?}: {}", s, e))))
         .collect()
 }
 
@@ -52,11 +70,19 @@ impl From<i64> for ParamMode {
     }
 }
 
+/// Where a machine gets its input from and sends its output to.
+///
+/// An error from `get` halts the machine, except for [`ErrorKind::WouldBlock`]
+/// which leaves it [blocked](IntCodeMachine::blocked) on the same instruction
+/// so it can be run again once there is input. An error from `put` halts it.
 pub trait IO {
+    /// The next input for the machine.
     fn get(&mut self) -> Result<i64>;
+    /// Takes an output of the machine.
     fn put(&mut self, val: i64) -> Result<()>;
 }
 
+/// Gives 0 for every input and throws away the outputs.
 pub struct NoIO {}
 
 impl IO for NoIO {
@@ -69,12 +95,48 @@ impl IO for NoIO {
     }
 }
 
+/// Feeds a fixed list of inputs and collects the outputs, the machine halts once
+/// it asks for more input than it was given.
+#[derive(Clone)]
+pub struct BufferedIO {
+    /// The inputs still to be read.
+    pub input: VecDeque<i64>,
+    /// Everything the machine output so far.
+    pub output: Vec<i64>,
+    /// Whether the machine asked for more input than it was given.
+    pub starved: bool,
+}
+
+impl BufferedIO {
+    /// IO that gives the machine `input` in order.
+    pub fn new(input: &[i64]) -> Self {
+        Self { input: input.iter().cloned().collect(), output: vec!(), starved: false }
+    }
+}
+
+impl IO for BufferedIO {
+    fn get(&mut self) -> Result<i64> {
+        let input = self.input.pop_front();
+        self.starved = input.is_none();
+        input.ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "Out of input"))
+    }
+
+    fn put(&mut self, val: i64) -> Result<()> {
+        self.output.push(val);
+        Ok(())
+    }
+}
+
+/// Connects a machine to channels, so it can run on its own thread and talk
+/// to other machines or to the code driving it.
 pub struct AsyncIO {
     tx: Sender<i64>,
     rx: Receiver<i64>,
 }
 
 impl AsyncIO {
+    /// The IO along with a sender of inputs to the machine and a receiver of
+    /// its outputs. The machine halts once either side is dropped.
     pub fn new() -> (Self, Sender<i64>, Receiver<i64>) {
         let (itx, orx) = channel();
         let (otx, irx) = channel();
@@ -93,20 +155,25 @@ impl IO for AsyncIO {
     }
 }
 
+/// Forwards the outputs of one machine to the inputs of others, e.g. to chain
+/// machines using [`AsyncIO`].
 pub struct Connector {
     tx: Vec<Sender<i64>>,
     rx: Receiver<i64>,
 }
 
 impl Connector {
+    /// Forwards everything from `rx` to `tx`.
     pub fn new(tx: Sender<i64>, rx: Receiver<i64>) -> Self {
         Self { tx: vec![tx], rx }
     }
 
+    /// Forwards everything from `rx` to each of `tx`.
     pub fn multiplexed(tx: Vec<Sender<i64>>, rx: Receiver<i64>) -> Self {
         Self { tx, rx }
     }
 
+    /// Forwards until the sending machine is gone.
     pub fn run(&self) {
         while let Ok(data) = self.rx.recv() {
             for tx in self.tx.iter() {
@@ -116,47 +183,134 @@ impl Connector {
     }
 }
 
+/// Called with the pc and raw opcode of every instruction before it executes.
+pub type Tracer = Box<dyn FnMut(usize, i64) + Send>;
+
+/// An Intcode computer running a program against some [`IO`].
 pub struct IntCodeMachine<T> where T: IO {
+    /// The memory of the machine, which starts out as the program and grows
+    /// as the program writes past its end.
     pub program: Vec<i64>,
+    /// Where the machine gets its input from and sends its output to.
     pub io: T,
     pc: usize,
     relative_base: i64,
     halted: bool,
+    blocked: bool,
+    cycles: usize,
+    tracer: Option<Tracer>,
+}
+
+/// Everything needed to put a machine back to an earlier point of its execution.
+#[derive(Clone)]
+pub struct MachineState {
+    /// The memory of the machine.
+    pub program: Vec<i64>,
+    /// The position of the next instruction.
+    pub pc: usize,
+    /// What relative parameters are relative to.
+    pub relative_base: i64,
+    /// Whether the machine had halted.
+    pub halted: bool,
+    /// How many instructions the machine had executed.
+    pub cycles: usize,
 }
 
 impl<T> IntCodeMachine<T> where T: IO {
-    pub fn new(program: &Vec<i64>, io: T) -> Self {
+    /// A machine about to run the first instruction of `program`.
+    pub fn new(program: &[i64], io: T) -> Self {
         Self {
             program: program.to_vec(),
             io: io,
             pc: 0,
             relative_base: 0,
             halted: false,
+            blocked: false,
+            cycles: 0,
+            tracer: None,
         }
     }
 
-    fn read_memory(&mut self, pos: usize) -> i64 {
-        if pos >= self.program.len() {
-            self.program.resize(pos + 1, 0);
+    /// Calls `tracer` before every instruction from now on.
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.tracer = Some(tracer);
+    }
+
+    /// The position of the next instruction.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// How many instructions have been executed.
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+
+    /// Whether the program has halted, or the machine halted it because of its IO.
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Set when the last instruction was an input that the IO couldn't answer yet.
+    pub fn blocked(&self) -> bool {
+        self.blocked
+    }
+
+    /// A snapshot of the machine to [restore](Self::restore_state) later on,
+    /// its IO is not part of it.
+    pub fn save_state(&self) -> MachineState {
+        MachineState {
+            program: self.program.clone(),
+            pc: self.pc,
+            relative_base: self.relative_base,
+            halted: self.halted,
+            cycles: self.cycles,
         }
-        self.program[pos]
     }
 
-    fn write_memory(&mut self, pos: usize, value: i64) {
+    /// Puts the machine back to how it was when `state` was saved.
+    pub fn restore_state(&mut self, state: &MachineState) {
+        self.program.clone_from(&state.program);
+        self.pc = state.pc;
+        self.relative_base = state.relative_base;
+        self.halted = state.halted;
+        self.blocked = false;
+        self.cycles = state.cycles;
+    }
+
+    // Memory grows on demand, but only up to the limit so that a bad address can't exhaust it
+    fn address(&mut self, pos: i64) -> usize {
+        if pos < 0 {
+            panic!("Negative address {}!", pos);
+        }
+        let pos = pos as usize;
+        if pos >= MEMORY_LIMIT {
+            panic!("Address {} is past the memory limit of {} values!", pos, MEMORY_LIMIT);
+        }
         if pos >= self.program.len() {
             self.program.resize(pos + 1, 0);
         }
+        pos
+    }
+
+    fn read_memory(&mut self, pos: i64) -> i64 {
+        let pos = self.address(pos);
+        self.program[pos]
+    }
+
+    fn write_memory(&mut self, pos: i64, value: i64) {
+        let pos = self.address(pos);
         self.program[pos] = value;
     }
 
     fn fetch_operand(&mut self, param_index: usize, param_mode: ParamMode) -> i64 {
         let param = self.program[self.pc + param_index + 1];
         match param_mode {
-            ParamMode::Position => self.read_memory(param as usize),
+            ParamMode::Position => self.read_memory(param),
             ParamMode::Immediate => param,
             ParamMode::Relative => {
                 let pos = param + self.relative_base;
-                self.read_memory(pos as usize)
+                self.read_memory(pos)
             }
         }
     }
@@ -188,7 +342,7 @@ impl<T> IntCodeMachine<T> where T: IO {
             Instruction::Multiply => num1 * num2,
             _ => panic!("Unexpected instruction!")
         };
-        self.write_memory(pos as usize, res);
+        self.write_memory(pos, res);
         self.pc += 4
     }
 
@@ -196,11 +350,14 @@ impl<T> IntCodeMachine<T> where T: IO {
         match inst {
             Instruction::Input => {
                 let pos = self.fetch_destination(0, param_modes[0]);
-                if let Ok(input) = self.io.get() {
-                    self.write_memory(pos as usize, input); 
-                    self.pc += 2;
-                } else {
-                    self.halted = true;
+                match self.io.get() {
+                    Ok(input) => {
+                        self.write_memory(pos, input); 
+                        self.pc += 2;
+                    },
+                    // Retry the same instruction once there's input available
+                    Err(ref e) if e.kind() == ErrorKind::WouldBlock => self.blocked = true,
+                    Err(_) => self.halted = true,
                 }
             },
             Instruction::Output => {
@@ -239,9 +396,9 @@ impl<T> IntCodeMachine<T> where T: IO {
         };
 
         if check_fn(num1, num2) {
-            self.write_memory(pos as usize, 1);
+            self.write_memory(pos, 1);
         } else {
-            self.write_memory(pos as usize, 0);
+            self.write_memory(pos, 0);
         }
 
         self.pc += 4
@@ -275,8 +432,19 @@ impl<T> IntCodeMachine<T> where T: IO {
         (inst, param_modes)
     }
 
-    fn step(&mut self) {
+    /// Executes a single instruction.
+    ///
+    /// # Panics
+    ///
+    /// On an invalid opcode or parameter mode, when the pc is out of memory, or
+    /// on an address that is negative or past the [`MEMORY_LIMIT`].
+    pub fn step(&mut self) {
         assert!(self.pc < self.program.len().try_into().unwrap());
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer(self.pc, self.program[self.pc]);
+        }
+        self.cycles += 1;
+        self.blocked = false;
         let (inst, param_modes) = self.decode_instruction();
         match inst {
             Instruction::Add => self.exec_arithmetic(Instruction::Add, param_modes),
@@ -292,9 +460,23 @@ impl<T> IntCodeMachine<T> where T: IO {
         }
     }
 
+    /// Runs until the machine halts.
     pub fn run(&mut self) {
         while !self.halted {
             self.step();
         }
     }
+
+    /// Same as [`run`](Self::run) but gives up after a number of instructions or
+    /// when it is blocked on input, returns whether the machine halted.
+    pub fn run_for(&mut self, max_cycles: usize) -> bool {
+        let limit = self.cycles + max_cycles;
+        while !self.halted && self.cycles < limit {
+            self.step();
+            if self.blocked {
+                break;
+            }
+        }
+        self.halted
+    }
 }
@@ -0,0 +1,96 @@
+use crate::solver::{Answer, DayResult, SolveError, Stage};
+use std::time::Duration;
+
+// A JSON string, multi-line answers keep their line breaks as \n
+pub fn string(s: &str) -> String {
+    let mut output = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.3}", d.as_secs_f64() * 1000.0)
+}
+
+fn line(year: i32, set: Option<&str>, day: i32, part: Option<u8>, answer: Option<&Answer>, parse_time: Option<Duration>, error: Option<&SolveError>) -> String {
+    // Only runs over named input sets say which one the line is for
+    let set = set.map_or(String::new(), |s| format!(",\"input_set\":{}", string(s)));
+    // Likewise only parts measured with --memory have their heap usage
+    let memory = answer.and_then(|a| a.memory)
+        .map_or(String::new(), |m| format!(",\"peak_bytes\":{},\"allocations\":{}", m.peak, m.allocations));
+    format!("{{\"year\":{}{},\"day\":{},\"part\":{},\"answer\":{},\"parse_ms\":{},\"time_ms\":{}{},\"error\":{}}}",
+            year, set, day,
+            part.map_or(String::from("null"), |p| p.to_string()),
+            answer.map_or(String::from("null"), |a| string(&a.value)),
+            parse_time.map_or(String::from("null"), millis),
+            answer.map_or(String::from("null"), |a| millis(a.time)),
+            memory,
+            error.map_or(String::from("null"), |e| string(&e.to_string())))
+}
+
+/* One line per part that ran. A day that failed gives a single line with the
+ * error, its part is null if it never got as far as running one.
+ */
+pub fn lines(year: i32, set: Option<&str>, day: i32, result: &Result<DayResult, SolveError>) -> Vec<String> {
+    match result {
+        Ok(r) => [(1, &r.first), (2, &r.second)].iter()
+            .filter_map(|(part, answer)| answer.as_ref().map(|a| line(year, set, day, Some(*part), Some(a), Some(r.parse_time), None)))
+            .collect(),
+        Err(e) => {
+            let part = match e.stage {
+                Stage::Part1 => Some(1),
+                Stage::Part2 => Some(2),
+                _ => None,
+            };
+            vec!(line(year, set, day, part, None, None, Some(e)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heap::Usage;
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(string("YLFPJ"), "\"YLFPJ\"");
+        assert_eq!(string(" 1\n1 "), "\" 1\\n1 \"");
+        assert_eq!(string("say \"hi\"\\\t\u{1}"), "\"say \\\"hi\\\"\\\\\\t\\u0001\"");
+    }
+
+    #[test]
+    fn lines_per_part() {
+        let answer = |value: &str| Some(Answer { value: value.to_string(), time: Duration::from_millis(2), memory: None });
+        let result = Ok(DayResult { parse_time: Duration::from_millis(1), parse_memory: None, first: answer("12"), second: answer("#.\n.#") });
+        assert_eq!(lines(2019, None, 8, &result), vec!(
+            "{\"year\":2019,\"day\":8,\"part\":1,\"answer\":\"12\",\"parse_ms\":1.000,\"time_ms\":2.000,\"error\":null}",
+            "{\"year\":2019,\"day\":8,\"part\":2,\"answer\":\"#.\\n.#\",\"parse_ms\":1.000,\"time_ms\":2.000,\"error\":null}",
+        ));
+
+        let result = Err(SolveError::new(3, Stage::Parse, "bad wire"));
+        assert_eq!(lines(2019, None, 3, &result), vec!(
+            "{\"year\":2019,\"day\":3,\"part\":null,\"answer\":null,\"parse_ms\":null,\"time_ms\":null,\"error\":\"Day 3 failed to parse its input: bad wire\"}",
+        ));
+        assert_eq!(lines(2019, Some("alice"), 3, &result), vec!(
+            "{\"year\":2019,\"input_set\":\"alice\",\"day\":3,\"part\":null,\"answer\":null,\"parse_ms\":null,\"time_ms\":null,\"error\":\"Day 3 failed to parse its input: bad wire\"}",
+        ));
+
+        let answer = Answer { value: String::from("7"), time: Duration::from_millis(2), memory: Some(Usage { peak: 4096, allocations: 3, allocated: 8192 }) };
+        let result = Ok(DayResult { parse_time: Duration::from_millis(1), parse_memory: None, first: Some(answer), second: None });
+        assert_eq!(lines(2019, None, 16, &result), vec!(
+            "{\"year\":2019,\"day\":16,\"part\":1,\"answer\":\"7\",\"parse_ms\":1.000,\"time_ms\":2.000,\"peak_bytes\":4096,\"allocations\":3,\"error\":null}",
+        ));
+    }
+}
@@ -0,0 +1,13 @@
+//! The shared infrastructure of these Advent of Code solutions, for other
+//! projects to build on: the [`intcode`] computer, [`grid`] utilities, and the
+//! [`solver`] interface the days implement along with running them.
+
+#![warn(missing_docs)]
+
+pub mod intcode;
+pub mod grid;
+pub mod solver;
+pub mod timing;
+pub mod heap;
+pub mod parse;
+pub mod visualize;
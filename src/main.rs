@@ -1,18 +1,164 @@
-#![feature(slice_group_by)]
-
 mod solutions;
-mod solver;
-mod intcode;
-mod grid;
+mod registry;
+mod fuzz;
+mod memview;
+mod server;
+mod bench;
+mod scaffold;
+mod pool;
+mod memo;
+mod cli;
+mod runner;
+mod jsonl;
+mod watch;
+mod answers;
+mod history;
+mod menu;
+#[cfg(test)]
+mod testing;
+
+// These live in the library for other projects to use, the binary keeps using them through crate::
+use aoc_2019::{grid, heap, intcode, parse, solver, timing, visualize};
+use crate::answers::Answers;
+use std::{
+    env, fs,
+    io::{stdin, stdout, IsTerminal},
+    process,
+};
 
-use crate::solutions::run_day;
-use std::env;
+// Only counts while --memory is measuring a stage
+#[global_allocator]
+static ALLOCATOR: heap::CountingAllocator = heap::CountingAllocator;
 
 fn main() {
-    let day = env::args()
-        .nth(1)
-        .unwrap_or_else(|| { println!("Given no input, running default day 1..."); "1".to_string()})
-        .parse()
-        .unwrap_or(1);
-    run_day(day)
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("fuzz") => return fuzz::run(&args[2..]),
+        Some("memview") => return memview::run(&args[2..]),
+        Some("serve") => return server::run(&args[2..]),
+        Some("bench") => return bench::run(&args[2..]),
+        Some("new") => return scaffold::run(&args[2..]),
+        Some("menu") => return menu::run(),
+        // Someone at a terminal gets to pick what to run
+        None if stdin().is_terminal() && stdout().is_terminal() => return menu::run(),
+        _ => (),
+    }
+
+    let cli = match cli::parse(&args[1..]) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+    if cli.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    if cli.list {
+        let registrations = match cli.year {
+            Some(year) => registry::for_year(year),
+            None => registry::all(),
+        };
+        for r in registrations.iter().filter(|r| cli.tag.as_ref().is_none_or(|t| r.has_tag(t))) {
+            println!("{} day {:>2}: {:<36} {}", r.year, r.day, r.title, r.tags.join(", "));
+        }
+        return;
+    }
+    if cli.watch {
+        return watch::run(&cli);
+    }
+    if args.len() == 1 {
+        println!("Given no input, running default day 1...");
+    }
+
+    let mut ok = true;
+    for set in cli.input_sets.iter() {
+        if let Some(name) = &set.name {
+            if !cli.jsonl {
+                println!("Input set {}", name);
+            }
+        }
+        ok &= run_set(&cli, set);
+    }
+    if !ok {
+        process::exit(1);
+    }
+}
+
+// Runs the days on one set of inputs, false if any of them failed or gave the wrong answer
+fn run_set(cli: &cli::Args, set: &cli::InputSet) -> bool {
+    let mut answers = match Answers::load_or_default(&set.answers_file) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("Unable to read the expected answers: {}", e);
+            process::exit(2);
+        }
+    };
+    let (mut passed, mut mismatched, mut missing) = (0, 0, 0);
+    // Keep stdout to the JSON lines when they are asked for
+    let report = |s: String| if cli.jsonl { eprint!("{}", s) } else { print!("{}", s) };
+
+    let results = runner::run_days(&cli.days, &set.options, cli.jobs, |(day, result)| {
+        let day = *day;
+        if cli.jsonl {
+            for line in jsonl::lines(set.options.year, set.name.as_deref(), day, result) {
+                println!("{}", line);
+            }
+        } else {
+            if cli.days.len() > 1 && !set.options.quiet {
+                println!("Day {}", day);
+            }
+            match result {
+                Ok(r) => r.print(set.options.quiet),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        if let Ok(r) = result {
+            for (part, answer) in [(1, &r.first), (2, &r.second)].iter() {
+                let answer = match answer {
+                    Some(a) => &a.value,
+                    None => continue,
+                };
+                if cli.record_answers {
+                    answers.set(day, *part, answer);
+                }
+                if !cli.verify {
+                    continue;
+                }
+                match answers.get(day, *part).map(|e| answers::diff(e, answer)) {
+                    Some(None) => { passed += 1; report(format!("Day {} part {}: PASS\n", day, part)); },
+                    Some(Some(diff)) => { mismatched += 1; report(format!("Day {} part {}: FAIL\n{}", day, part, diff)); },
+                    None => { missing += 1; report(format!("Day {} part {}: no expected answer\n", day, part)); },
+                }
+            }
+        }
+    });
+
+    history::record(set.options.year, &results);
+    if cli.verify {
+        report(format!("{} passed, {} failed, {} without an expected answer\n", passed, mismatched, missing));
+    }
+    if cli.record_answers {
+        if let Err(e) = answers.save(&set.answers_file) {
+            eprintln!("Unable to write answers to {}: {}", set.answers_file, e);
+        }
+    }
+
+    if cli.timings {
+        report(timing::table(&results));
+    }
+    if set.options.memory {
+        report(heap::table(&results));
+    }
+    if let Some(path) = &cli.timings_out {
+        let report = if path.ends_with(".csv") { timing::csv(&results) } else { timing::json(&results) };
+        if let Err(e) = fs::write(path, report) {
+            eprintln!("Unable to write timings to {}: {}", path, e);
+        }
+    }
+
+    // Nothing was verified for an answer that isn't known yet
+    let missing_ok = !cli.verify || cli.allow_missing || missing == 0;
+    !results.iter().any(|(_, r)| r.is_err()) && mismatched == 0 && missing_ok
 }
@@ -0,0 +1,246 @@
+use crate::{intcode::BufferedIO, pool::MachinePool};
+use std::{
+    collections::HashMap,
+    env,
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+};
+
+// Directory to persist caches in between runs, caching is in memory only if unset
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+/* Wraps a program that behaves like a pure function, i.e. every run starts from
+ * the pristine image, consumes exactly its inputs and produces the same number
+ * of outputs. The number of inputs and outputs is taken from the first run that
+ * consumes all of its inputs and any run that doesn't match it is rejected
+ * rather than cached.
+ */
+pub struct PureProgram {
+    pool: MachinePool,
+    program_hash: u64,
+    arity: Mutex<Option<(usize, usize)>>,
+    cache: Mutex<HashMap<Vec<i64>, Vec<i64>>>,
+    path: Option<PathBuf>,
+}
+
+impl PureProgram {
+    pub fn new(program: &[i64]) -> Self {
+        Self {
+            pool: MachinePool::new(program),
+            program_hash: program_hash(program),
+            arity: Mutex::new(None),
+            cache: Mutex::new(HashMap::new()),
+            path: None,
+        }
+    }
+
+    // Persists under the cache directory from the environment, if there is one
+    pub fn from_env(program: &[i64], name: &str) -> Self {
+        match env::var(CACHE_DIR_VAR) {
+            Ok(dir) => Self::new(program).with_cache_file(PathBuf::from(dir).join(format!("{}.cache", name))),
+            Err(_) => Self::new(program),
+        }
+    }
+
+    // Loads any results already in the file, it is ignored if it was made for a different program
+    pub fn with_cache_file(mut self, path: PathBuf) -> Self {
+        if let Ok(contents) = fs::read_to_string(&path) {
+            let mut lines = contents.lines();
+            if lines.next() == Some(&self.program_hash.to_string()) {
+                let mut cache = self.cache.lock().unwrap();
+                for line in lines {
+                    if let Some((input, output)) = parse_entry(line) {
+                        self.arity.lock().unwrap().get_or_insert((input.len(), output.len()));
+                        cache.insert(input, output);
+                    }
+                }
+            }
+        }
+        self.path = Some(path);
+        self
+    }
+
+    pub fn call(&self, input: &[i64]) -> Result<Vec<i64>, String> {
+        self.call_batch(&[input.to_vec()]).pop().unwrap()
+    }
+
+    // Only the inputs that haven't been seen before get executed
+    pub fn call_batch(&self, inputs: &[Vec<i64>]) -> Vec<Result<Vec<i64>, String>> {
+        let missing: Vec<Vec<i64>> = {
+            let cache = self.cache.lock().unwrap();
+            let mut missing: Vec<_> = inputs.iter().filter(|i| !cache.contains_key(*i)).cloned().collect();
+            missing.sort();
+            missing.dedup();
+            missing
+        };
+
+        let mut rejected = HashMap::new();
+        if !missing.is_empty() {
+            match self.pool.run_batch(missing.clone()) {
+                Ok(results) => self.accept(missing, results, &mut rejected),
+                // None of the new inputs get cached, a later call tries them again
+                Err(e) => rejected.extend(missing.into_iter().map(|i| (i, e.clone()))),
+            }
+        }
+
+        let cache = self.cache.lock().unwrap();
+        inputs.iter().map(|i| {
+            match rejected.get(i) {
+                Some(e) => Err(e.clone()),
+                None => Ok(cache[i].clone()),
+            }
+        }).collect()
+    }
+
+    // Caches the runs that consumed exactly their inputs and match the arity
+    fn accept(&self, missing: Vec<Vec<i64>>, results: Vec<BufferedIO>, rejected: &mut HashMap<Vec<i64>, String>) {
+        let mut arity = self.arity.lock().unwrap();
+        let mut cache = self.cache.lock().unwrap();
+        for (input, io) in missing.into_iter().zip(results) {
+            if io.starved || !io.input.is_empty() {
+                let message = format!("Run didn't consume exactly the {} inputs it was given", input.len());
+                rejected.insert(input, message);
+                continue;
+            }
+            // Only a run that is accepted decides the arity
+            let (inputs_wanted, outputs) = arity.unwrap_or((input.len(), io.output.len()));
+            if input.len() != inputs_wanted {
+                rejected.insert(input, format!("Run didn't consume exactly {} inputs", inputs_wanted));
+            } else if io.output.len() != outputs {
+                rejected.insert(input, format!("Run produced {} outputs instead of {}", io.output.len(), outputs));
+            } else {
+                *arity = Some((inputs_wanted, outputs));
+                cache.insert(input, io.output);
+            }
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = match &self.path {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut contents = format!("{}\n", self.program_hash);
+        for (input, output) in self.cache.lock().unwrap().iter() {
+            contents.push_str(&format!("{}:{}\n", join(input), join(output)));
+        }
+        fs::write(path, contents)
+    }
+}
+
+// FNV-1a, unlike DefaultHasher it gives the same hash with every Rust release so the cache files stay valid
+fn program_hash(program: &[i64]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    program.iter()
+        .flat_map(|v| v.to_le_bytes())
+        .fold(OFFSET_BASIS, |hash, b| (hash ^ u64::from(b)).wrapping_mul(PRIME))
+}
+
+fn join(values: &[i64]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn parse_entry(line: &str) -> Option<(Vec<i64>, Vec<i64>)> {
+    let mut split = line.split(':');
+    let parse = |s: &str| -> Option<Vec<i64>> {
+        s.split(',').filter(|v| !v.is_empty()).map(|v| v.parse().ok()).collect()
+    };
+    Some((parse(split.next()?)?, parse(split.next()?)?))
+}
+
+impl Drop for PureProgram {
+    fn drop(&mut self) {
+        if let Err(e) = self.save() {
+            eprintln!("Unable to save the cache for the program: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads two numbers and outputs their sum
+    const ADD: [i64; 14] = [3, 11, 3, 12, 1, 11, 12, 13, 4, 13, 99, 0, 0, 0];
+
+    fn cache_file(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aoc_memo_{}_{}.cache", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn stable_hash() {
+        assert_eq!(program_hash(&ADD), 15235771079658102211);
+    }
+
+    #[test]
+    fn accepts_runs() {
+        let memo = PureProgram::new(&ADD);
+        assert_eq!(memo.call(&[1, 2]), Ok(vec!(3)));
+        assert_eq!(memo.call_batch(&[vec!(3, 4), vec!(1, 2)]), vec!(Ok(vec!(7)), Ok(vec!(3))));
+        assert_eq!(*memo.arity.lock().unwrap(), Some((2, 1)));
+        assert_eq!(memo.cache.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rejects_runs() {
+        let memo = PureProgram::new(&ADD);
+        assert!(memo.call(&[1]).is_err());
+        assert!(memo.call(&[1, 2, 3]).is_err());
+        // The rejected runs didn't decide the arity
+        assert_eq!(*memo.arity.lock().unwrap(), None);
+        assert!(memo.cache.lock().unwrap().is_empty());
+
+        assert_eq!(memo.call(&[1, 2]), Ok(vec!(3)));
+        assert_eq!(*memo.arity.lock().unwrap(), Some((2, 1)));
+    }
+
+    #[test]
+    fn rejects_failed_batches() {
+        // Loops forever on a 0, halts on anything else
+        let path = cache_file("failed");
+        let loops = [3, 9, 1005, 9, 8, 1105, 1, 5, 99, 0];
+        fs::write(&path, format!("{}\n2:\n", program_hash(&loops))).unwrap();
+        let mut memo = PureProgram::new(&loops).with_cache_file(path.clone());
+        memo.pool.cycle_budget = 100;
+        let results = memo.call_batch(&[vec!(2), vec!(1), vec!(0)]);
+        // The cached input still gets its answer
+        assert_eq!(results[0], Ok(vec!()));
+        assert!(results[1].is_err() && results[2].is_err());
+        assert_eq!(memo.call(&[1]), Ok(vec!()));
+        drop(memo);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn cache_hits() {
+        let path = cache_file("hits");
+        // The program would answer 3, so 42 can only come from the file
+        fs::write(&path, format!("{}\n1,2:42\n", program_hash(&ADD))).unwrap();
+        let memo = PureProgram::new(&ADD).with_cache_file(path.clone());
+        assert_eq!(memo.call(&[1, 2]), Ok(vec!(42)));
+        assert_eq!(memo.call(&[2, 2]), Ok(vec!(4)));
+        drop(memo);
+
+        let memo = PureProgram::new(&ADD).with_cache_file(path.clone());
+        assert_eq!(memo.cache.lock().unwrap().len(), 2);
+        drop(memo);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn ignores_other_programs() {
+        let path = cache_file("other");
+        fs::write(&path, format!("{}\n1,2:42\n", program_hash(&ADD) ^ 1)).unwrap();
+        let memo = PureProgram::new(&ADD).with_cache_file(path.clone());
+        assert_eq!(memo.call(&[1, 2]), Ok(vec!(3)));
+        drop(memo);
+        fs::remove_file(&path).unwrap();
+    }
+}
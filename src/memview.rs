@@ -0,0 +1,273 @@
+use crate::{
+    intcode::{parse_program, IntCodeMachine, IO},
+    solver::{input_file, DEFAULT_YEAR},
+};
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, ErrorKind},
+    process,
+};
+
+const CELLS_PER_ROW: usize = 16;
+const DEFAULT_MAX_CYCLES: usize = 10000000;
+const DEFAULT_MAX_SNAPSHOTS: usize = 1000;
+const HIGHLIGHT: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+// Plays back the given inputs, then keeps answering with the fill value if there is one
+struct ScriptedIO {
+    input: VecDeque<i64>,
+    fill: Option<i64>,
+    outputs: usize,
+}
+
+impl IO for ScriptedIO {
+    fn get(&mut self) -> io::Result<i64> {
+        self.input
+            .pop_front()
+            .or(self.fill)
+            .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "Out of input"))
+    }
+
+    fn put(&mut self, _val: i64) -> io::Result<()> {
+        self.outputs += 1;
+        Ok(())
+    }
+}
+
+pub enum Trigger {
+    Cycles(usize),
+    Outputs(usize),
+}
+
+#[derive(Clone)]
+pub struct Snapshot {
+    pub cycle: usize,
+    pub memory: Vec<i64>,
+}
+
+impl Snapshot {
+    fn cell(&self, address: usize) -> i64 {
+        *self.memory.get(address).unwrap_or(&0)
+    }
+
+    // Cells that differ between two snapshots as (address, before, after), memory
+    // that only one of them has grown into counts as zero in the other
+    pub fn diff(&self, after: &Snapshot) -> Vec<(usize, i64, i64)> {
+        let len = self.memory.len().max(after.memory.len());
+        (0..len)
+            .map(|a| (a, self.cell(a), after.cell(a)))
+            .filter(|(_, before, after)| before != after)
+            .collect()
+    }
+
+    // Hex dump style grid, changed cells are highlighted and unchanged rows are
+    // skipped when only_changed is set
+    pub fn render(&self, previous: Option<&Snapshot>, only_changed: bool) -> String {
+        let changed: Vec<usize> = previous.map_or(vec!(), |p| p.diff(self).iter().map(|(a, _, _)| *a).collect());
+        let width = self.memory.iter().map(|v| format_cell(*v).len()).max().unwrap_or(1);
+        let mut output = String::new();
+
+        for (row, cells) in self.memory.chunks(CELLS_PER_ROW).enumerate() {
+            let start = row * CELLS_PER_ROW;
+            let row_changed = changed.iter().any(|a| *a >= start && *a < start + CELLS_PER_ROW);
+            if only_changed && !row_changed {
+                continue;
+            }
+            output.push_str(&format!("{:06x}:", start));
+            for (i, v) in cells.iter().enumerate() {
+                let cell = format!("{:>width$}", format_cell(*v), width = width);
+                if changed.contains(&(start + i)) {
+                    output.push_str(&format!(" {}{}{}", HIGHLIGHT, cell, RESET));
+                } else {
+                    output.push_str(&format!(" {}", cell));
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+fn format_cell(v: i64) -> String {
+    if v < 0 {
+        format!("-{:x}", v.unsigned_abs())
+    } else {
+        format!("{:x}", v)
+    }
+}
+
+pub fn diff_report(before: &Snapshot, after: &Snapshot) -> String {
+    let mut report = format!("# cycle {} -> cycle {}\naddress,before,after\n", before.cycle, after.cycle);
+    for (address, old, new) in before.diff(after) {
+        report.push_str(&format!("{},{},{}\n", address, old, new));
+    }
+    report
+}
+
+// Runs the program and takes a snapshot at the start, every time the trigger
+// fires and once the machine stops
+pub fn record(program: &[i64], input: &[i64], fill: Option<i64>, trigger: &Trigger,
+              max_cycles: usize, max_snapshots: usize) -> Vec<Snapshot> {
+    let io = ScriptedIO { input: input.iter().cloned().collect(), fill, outputs: 0 };
+    let mut machine = IntCodeMachine::new(program, io);
+    let mut snapshots = vec!(Snapshot { cycle: 0, memory: machine.program.clone() });
+    let mut last_outputs = 0;
+
+    while !machine.halted() && machine.cycles() < max_cycles && snapshots.len() < max_snapshots {
+        machine.step();
+        let fire = match trigger {
+            Trigger::Cycles(n) => machine.cycles().is_multiple_of(*n),
+            Trigger::Outputs(n) => {
+                let fired = machine.io.outputs != last_outputs && machine.io.outputs.is_multiple_of(*n);
+                last_outputs = machine.io.outputs;
+                fired
+            }
+        };
+        if fire {
+            snapshots.push(Snapshot { cycle: machine.cycles(), memory: machine.program.clone() });
+        }
+    }
+
+    if snapshots.last().map(|s| s.cycle) != Some(machine.cycles()) {
+        snapshots.push(Snapshot { cycle: machine.cycles(), memory: machine.program.clone() });
+    }
+    snapshots
+}
+
+fn parse_values(s: &str) -> Vec<i64> {
+    s.split(',').filter(|v| !v.is_empty()).filter_map(|v| v.trim().parse().ok()).collect()
+}
+
+const USAGE: &str = "Usage: memview <day> [--every N | --outputs N] [--input a,b,..] [--fill V] \
+[--set addr=value] [--max-cycles N] [--max-snapshots N] [--all-rows] [--diff A B [--out FILE]]";
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+// Usage: see USAGE
+pub fn run(args: &[String]) {
+    let day: i32 = match args.first().and_then(|d| d.parse().ok()) {
+        Some(d) => d,
+        None => usage_error("Expected a day"),
+    };
+
+    let mut trigger = Trigger::Outputs(1);
+    let mut input = vec!();
+    let mut fill = None;
+    let mut patches: Vec<(usize, i64)> = vec!();
+    let mut max_cycles = DEFAULT_MAX_CYCLES;
+    let mut max_snapshots = DEFAULT_MAX_SNAPSHOTS;
+    let mut only_changed = true;
+    let mut diff = None;
+    let mut out = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str).unwrap_or("");
+        match args[i].as_str() {
+            "--every" => { trigger = Trigger::Cycles(value.parse().unwrap_or(1).max(1)); i += 1; },
+            "--outputs" => { trigger = Trigger::Outputs(value.parse().unwrap_or(1).max(1)); i += 1; },
+            "--input" => { input = parse_values(value); i += 1; },
+            "--fill" => { fill = value.parse().ok(); i += 1; },
+            "--set" => {
+                let mut split = value.split('=');
+                match (split.next().and_then(|a| a.parse().ok()), split.next().and_then(|v| v.parse().ok())) {
+                    (Some(a), Some(v)) => patches.push((a, v)),
+                    _ => usage_error(&format!("Invalid --set {}, expected addr=value", value)),
+                }
+                i += 1;
+            },
+            "--max-cycles" => { max_cycles = value.parse().unwrap_or(max_cycles); i += 1; },
+            "--max-snapshots" => { max_snapshots = value.parse().unwrap_or(max_snapshots); i += 1; },
+            "--all-rows" => only_changed = false,
+            "--diff" => {
+                let to = args.get(i + 2).and_then(|b| b.parse::<usize>().ok());
+                match (value.parse::<usize>().ok(), to) {
+                    (Some(a), Some(b)) => diff = Some((a, b)),
+                    _ => usage_error("--diff expects two snapshot numbers"),
+                }
+                i += 2;
+            },
+            "--out" => { out = Some(value.to_string()); i += 1; },
+            a => usage_error(&format!("Unknown option {}", a)),
+        }
+        i += 1;
+    }
+
+    let mut program = match File::open(input_file(DEFAULT_YEAR, day)).and_then(parse_program) {
+        Ok(p) => p,
+        Err(e) => { eprintln!("Unable to read {}: {}", input_file(DEFAULT_YEAR, day), e); process::exit(1); }
+    };
+    for (address, value) in patches {
+        if address >= program.len() {
+            program.resize(address + 1, 0);
+        }
+        program[address] = value;
+    }
+
+    let snapshots = record(&program, &input, fill, &trigger, max_cycles, max_snapshots);
+    println!("Took {} snapshots", snapshots.len());
+
+    match diff {
+        Some((a, b)) => {
+            let (before, after) = match (snapshots.get(a), snapshots.get(b)) {
+                (Some(before), Some(after)) => (before, after),
+                _ => usage_error(&format!("Snapshots range from 0 to {}", snapshots.len() - 1)),
+            };
+            print!("{}", after.render(Some(before), only_changed));
+            let report = diff_report(before, after);
+            match out {
+                Some(path) => match fs::write(&path, report) {
+                    Ok(_) => println!("Wrote diff report to {}", path),
+                    Err(e) => { eprintln!("Unable to write {}: {}", path, e); process::exit(1); },
+                },
+                None => print!("{}", report),
+            }
+        },
+        None => {
+            for (i, snapshot) in snapshots.iter().enumerate() {
+                let previous = if i == 0 { None } else { snapshots.get(i - 1) };
+                let changes = previous.map_or(0, |p| p.diff(snapshot).len());
+                println!("Snapshot {} at cycle {}, {} cells changed", i, snapshot.cycle, changes);
+                print!("{}", snapshot.render(previous, only_changed && previous.is_some()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Doubles its input into address 10 and outputs it
+    const DOUBLE: [i64; 11] = [3, 9, 102, 2, 9, 10, 4, 10, 99, 0, 0];
+
+    #[test]
+    fn diffs_snapshots() {
+        let snapshots = record(&DOUBLE, &[5], None, &Trigger::Outputs(1), DEFAULT_MAX_CYCLES, DEFAULT_MAX_SNAPSHOTS);
+        assert_eq!(snapshots.iter().map(|s| s.cycle).collect::<Vec<_>>(), vec!(0, 3, 4));
+        let (before, after) = (&snapshots[0], &snapshots[1]);
+        assert_eq!(before.diff(after), vec!((9, 0, 5), (10, 0, 10)));
+        assert_eq!(after.diff(&snapshots[2]), vec!());
+        assert_eq!(diff_report(before, after), "# cycle 0 -> cycle 3\naddress,before,after\n9,0,5\n10,0,10\n");
+    }
+
+    #[test]
+    fn renders_snapshots() {
+        let before = Snapshot { cycle: 0, memory: DOUBLE.to_vec() };
+        let after = Snapshot { cycle: 3, memory: vec!(3, 9, 102, 2, 9, 10, 4, 10, 99, 5, 10, -1) };
+        assert_eq!(before.render(None, false), "000000:  3  9 66  2  9  a  4  a 63  0  0\n");
+        let highlighted = format!("000000:  3  9 66  2  9  a  4  a 63 {h} 5{r} {h} a{r} {h}-1{r}\n", h = HIGHLIGHT, r = RESET);
+        assert_eq!(after.render(Some(&before), true), highlighted);
+        // Unchanged rows are skipped
+        let long = Snapshot { cycle: 0, memory: vec!(0; 20) };
+        let mut changed = long.clone();
+        changed.memory[17] = 1;
+        assert_eq!(changed.render(Some(&long), true).lines().count(), 1);
+        assert_eq!(changed.render(Some(&long), false).lines().count(), 2);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    answers::{self, Answers},
+    history,
+    registry::{self, Registration},
+    solver::{input_root, input_set_dir, DayResult, InputSource, Options, DEFAULT_YEAR},
+    timing::format_time,
+};
+use std::{
+    fs,
+    io::{stdin, stdout, Write},
+};
+
+const HELP: &str = "\
+Commands:
+  <DAY> [PART]   run a day, or only one of its parts
+  play <DAY>     start a live session of a day that can be played by hand
+  set [NAME]     use the inputs of an input set, the usual ones without a name
+  year <YEAR>    show the days of YEAR
+  list           show the days again
+  help           show this message
+  quit           leave the menu";
+
+struct Menu {
+    year: i32,
+    root: String,
+    set: Option<String>,
+}
+
+impl Menu {
+    fn input_dir(&self) -> String {
+        match &self.set {
+            Some(name) => input_set_dir(&self.root, name),
+            None => self.root.clone(),
+        }
+    }
+
+    fn answers_file(&self) -> String {
+        match &self.set {
+            Some(_) => answers::set_file(&self.input_dir(), self.year),
+            None => answers::default_file(&self.root, self.year),
+        }
+    }
+
+    fn options(&self, part: Option<u8>) -> Options {
+        Options { year: self.year, part, input: InputSource::Dir(self.input_dir()), progress: true, ..Options::default() }
+    }
+
+    // The directories of the input root, other than those holding the inputs of other years
+    fn input_sets(&self) -> Vec<String> {
+        let mut sets: Vec<String> = fs::read_dir(&self.root).into_iter().flatten()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|name| !name.chars().all(|c| c.is_ascii_digit()))
+            .collect();
+        sets.sort();
+        sets
+    }
+
+    fn list(&self) {
+        let last_times = history::load();
+        println!("Advent of Code {}, reading the inputs from {}", self.year, self.input_dir());
+        let sets = self.input_sets();
+        if !sets.is_empty() {
+            println!("Input sets: {}", sets.join(", "));
+        }
+        let registrations = registry::for_year(self.year);
+        if registrations.is_empty() {
+            println!("None of the days of {} have been attempted yet", self.year);
+        }
+        for r in registrations {
+            println!("{:>4}  {:<36} {:>10}{}", r.day, r.title, format_time(last_times.get(&(r.year, r.day)).copied()),
+                     if r.solver.interactive() { "  can be played" } else { "" });
+        }
+    }
+
+    fn find(&self, day: &str) -> Result<&'static Registration, String> {
+        let day: i32 = day.parse().map_err(|_| format!("Invalid day {}", day))?;
+        registry::find(self.year, day).ok_or(format!("Day {} of {} hasn't been attempted yet", day, self.year))
+    }
+
+    // The answers along with how long they took and whether they are the expected ones
+    fn report(&self, day: i32, result: &DayResult) {
+        result.print(false);
+        let answers = Answers::load_or_default(&self.answers_file()).unwrap_or_default();
+        for (part, answer) in [(1, &result.first), (2, &result.second)] {
+            let answer = match answer {
+                Some(a) => a,
+                None => continue,
+            };
+            let check = match answers.get(day, part).map(|e| answers::diff(e, &answer.value)) {
+                Some(None) => "correct",
+                Some(Some(_)) => "differs from the expected answer",
+                None => "no expected answer",
+            };
+            println!("Part {} took {}, {}", part, format_time(Some(answer.time)), check);
+        }
+    }
+
+    fn run(&self, day: &str, part: Option<&str>) -> Result<(), String> {
+        let r = self.find(day)?;
+        let part = match part {
+            None => None,
+            Some("1") => Some(1),
+            Some("2") => Some(2),
+            Some(p) => return Err(format!("Invalid part {}, expected 1 or 2", p)),
+        };
+
+        let result = r.run(&self.options(part));
+        match &result {
+            Ok(result) => self.report(r.day, result),
+            Err(e) => println!("{}", e),
+        }
+        history::record(self.year, &[(r.day, result)]);
+        Ok(())
+    }
+
+    fn play(&self, day: &str) -> Result<(), String> {
+        let r = self.find(day)?;
+        if !r.solver.interactive() {
+            return Err(format!("Day {} can't be played by hand", r.day));
+        }
+        println!("Starting day {}, end the session with Ctrl-D", r.day);
+        r.solver.play(r.day, &self.options(None)).map_err(|e| e.to_string())
+    }
+
+    // Returns false once it is time to leave
+    fn command(&mut self, line: &str) -> Result<bool, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => (),
+            ["q"] | ["quit"] | ["exit"] => return Ok(false),
+            ["h"] | ["help"] => println!("{}", HELP),
+            ["l"] | ["list"] => self.list(),
+            ["play", day] => self.play(day)?,
+            ["set"] => { self.set = None; self.list(); },
+            ["set", name] => {
+                if !self.input_sets().iter().any(|s| s == name) {
+                    return Err(format!("There is no input set {} in {}", name, self.root));
+                }
+                self.set = Some(name.to_string());
+                self.list();
+            },
+            ["year", year] => {
+                self.year = year.parse().map_err(|_| format!("Invalid year {}", year))?;
+                self.list();
+            },
+            [day] => self.run(day, None)?,
+            [day, part] => self.run(day, Some(part))?,
+            _ => return Err(format!("Unknown command {}, try help", line.trim())),
+        }
+        Ok(true)
+    }
+}
+
+/* A menu on the terminal to pick the days to run from, shown when the binary
+ * is run without arguments. Reads a command per line until quit or Ctrl-D.
+ */
+pub fn run() {
+    let mut menu = Menu { year: DEFAULT_YEAR, root: input_root(), set: None };
+    menu.list();
+    println!("\n{}", HELP);
+
+    loop {
+        print!("\n> ");
+        let _ = stdout().flush();
+        let mut line = String::new();
+        match stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => (),
+        }
+        match menu.command(&line) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(e) => println!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Small parsers for the shapes puzzle inputs come in, reporting where in the
+//! input they went wrong.
+//!
+//! A [`Scanner`] works through a line, and [`lines`] runs one over every line
+//! of an input:
+//!
+//! ```
+//! use aoc_2019::parse::{self, Scanner};
+//!
+//! // An orbit of B around A on each line
+//! let orbit = |s: &mut Scanner| {
+//!     let centre = s.word()?.to_string();
+//!     s.expect(")")?;
+//!     Ok((centre, s.word()?.to_string()))
+//! };
+//! let orbits = parse::lines("COM)B\nB)C".as_bytes(), orbit).unwrap();
+//! assert_eq!(orbits[1], (String::from("B"), String::from("C")));
+//!
+//! let error = parse::lines("COM)B\nB(C".as_bytes(), orbit).unwrap_err();
+//! assert_eq!(error.to_string(), "line 2, column 2: expected \")\", found \"(C\"");
+//! ```
+
+use crate::solver;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io::{BufRead, BufReader, Read},
+    str::FromStr,
+};
+
+/// Where and why an input couldn't be parsed, lines and columns count from 1.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    /// The line the error is on.
+    pub line: usize,
+    /// The character of the line the error starts at.
+    pub column: usize,
+    /// What was wrong.
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// An amount of something, e.g. a chemical in a recipe.
+pub type Quantity<'a, T> = (T, &'a str);
+
+/// Works through a line of input from left to right.
+pub struct Scanner<'a> {
+    text: &'a str,
+    line: usize,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// A scanner at the start of `text`, which is line `line` of the input.
+    pub fn new(text: &'a str, line: usize) -> Self {
+        Self { text, line, pos: 0 }
+    }
+
+    /// The column the scanner has got to.
+    pub fn column(&self) -> usize {
+        self.text[..self.pos].chars().count() + 1
+    }
+
+    /// An error at the current column.
+    pub fn error(&self, message: impl Display) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Display) -> ParseError {
+        ParseError { line: self.line, column: self.text[..pos].chars().count() + 1, message: message.to_string() }
+    }
+
+    /// What is left of the line.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    /// Whether the whole line has been read.
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    /// Fails unless the whole line has been read.
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected {:?}", self.rest())))
+        }
+    }
+
+    /// Skips any spaces and tabs.
+    pub fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Reads `literal` if the line continues with it.
+    pub fn eat(&mut self, literal: &str) -> bool {
+        let found = self.rest().starts_with(literal);
+        if found {
+            self.pos += literal.len();
+        }
+        found
+    }
+
+    /// Reads `literal`, which the line has to continue with.
+    pub fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        if self.eat(literal) {
+            Ok(())
+        } else if self.is_empty() {
+            Err(self.error(format!("expected {:?}, found the end of the line", literal)))
+        } else {
+            Err(self.error(format!("expected {:?}, found {:?}", literal, self.rest())))
+        }
+    }
+
+    /// Reads the first of the `choices` the line continues with and gives what
+    /// goes with it, e.g. a direction for each of `U`, `D`, `L` and `R`.
+    pub fn one_of<T: Clone>(&mut self, choices: &[(&str, T)]) -> Result<T, ParseError> {
+        match choices.iter().find(|(literal, _)| self.eat(literal)) {
+            Some((_, value)) => Ok(value.clone()),
+            None => {
+                let expected: Vec<String> = choices.iter().map(|(literal, _)| format!("{:?}", literal)).collect();
+                Err(self.error(format!("expected one of {}, found {:?}", expected.join(", "), self.rest())))
+            },
+        }
+    }
+
+    /// Reads a single character.
+    pub fn char(&mut self) -> Result<char, ParseError> {
+        let c = self.rest().chars().next().ok_or_else(|| self.error("expected a character, found the end of the line"))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    /// Reads letters, digits and underscores, at least one of them.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+        let len = rest.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error(format!("expected a word, found {:?}", rest)));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Reads a whole number, which may start with a sign.
+    pub fn integer<T: FromStr>(&mut self) -> Result<T, ParseError>
+    where
+        T::Err: Display,
+    {
+        let start = self.pos;
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-') || rest.starts_with('+'));
+        let digits = rest[sign..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return Err(self.error(format!("expected a number, found {:?}", rest)));
+        }
+        self.pos += sign + digits;
+        rest[..sign + digits].parse().map_err(|e| self.error_at(start, format!("invalid number {:?}: {}", &rest[..sign + digits], e)))
+    }
+
+    /// Reads `label` followed by a number, e.g. `x=-3`.
+    pub fn labelled<T: FromStr>(&mut self, label: &str) -> Result<T, ParseError>
+    where
+        T::Err: Display,
+    {
+        self.expect(label)?;
+        self.integer()
+    }
+
+    /// Reads items with `separator` and optional whitespace between them,
+    /// there has to be at least one.
+    pub fn separated<T>(&mut self, separator: &str, mut item: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        let mut items = vec!(item(self)?);
+        loop {
+            self.skip_whitespace();
+            if !self.eat(separator) {
+                return Ok(items);
+            }
+            self.skip_whitespace();
+            items.push(item(self)?);
+        }
+    }
+
+    /// Reads numbers separated by `separator`, e.g. `1,-2,3`.
+    pub fn integers<T: FromStr>(&mut self, separator: &str) -> Result<Vec<T>, ParseError>
+    where
+        T::Err: Display,
+    {
+        self.separated(separator, |s| s.integer())
+    }
+
+    /// Reads a vector of labelled numbers between angle brackets, e.g.
+    /// `<x=1, y=-2, z=3>` with the labels `["x", "y", "z"]`.
+    pub fn vector<T: FromStr>(&mut self, labels: &[&str]) -> Result<Vec<T>, ParseError>
+    where
+        T::Err: Display,
+    {
+        self.expect("<")?;
+        let mut values = vec!();
+        for (i, label) in labels.iter().enumerate() {
+            if i > 0 {
+                self.expect(",")?;
+                self.skip_whitespace();
+            }
+            values.push(self.labelled(&format!("{}=", label))?);
+        }
+        self.expect(">")?;
+        Ok(values)
+    }
+
+    /// Reads an amount of something, e.g. `7 ORE`.
+    pub fn quantity<T: FromStr>(&mut self) -> Result<Quantity<'a, T>, ParseError>
+    where
+        T::Err: Display,
+    {
+        let amount = self.integer()?;
+        self.expect(" ")?;
+        Ok((amount, self.word()?))
+    }
+
+    /// Reads a recipe of the quantities it takes and the quantity it makes,
+    /// e.g. `7 A, 1 B => 1 C`.
+    pub fn recipe<T: FromStr>(&mut self) -> Result<(Vec<Quantity<'a, T>>, Quantity<'a, T>), ParseError>
+    where
+        T::Err: Display,
+    {
+        let inputs = self.separated(",", |s| s.quantity())?;
+        self.skip_whitespace();
+        self.expect("=>")?;
+        self.skip_whitespace();
+        Ok((inputs, self.quantity()?))
+    }
+}
+
+/// Parses every line of an input that isn't blank with `parse_line`, which has
+/// to read the whole line apart from any trailing whitespace.
+pub fn lines<R: Read, T>(r: R, mut parse_line: impl FnMut(&mut Scanner) -> Result<T, ParseError>) -> solver::Result<Vec<T>> {
+    let mut items = vec!();
+    for (i, line) in BufReader::new(r).lines().enumerate() {
+        let line = line?;
+        // Inputs saved on Windows end their lines with \r
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let mut scanner = Scanner::new(line, i + 1);
+        items.push(parse_line(&mut scanner)?);
+        scanner.end()?;
+    }
+    Ok(items)
+}
+
+/// Parses a number on each line.
+pub fn integer_lines<R: Read, T: FromStr>(r: R) -> solver::Result<Vec<T>>
+where
+    T::Err: Display,
+{
+    lines(r, |s| s.integer())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan<'a, T>(text: &'a str, f: impl FnOnce(&mut Scanner<'a>) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let mut scanner = Scanner::new(text, 1);
+        let result = f(&mut scanner)?;
+        scanner.end()?;
+        Ok(result)
+    }
+
+    fn error(column: usize, message: &str) -> ParseError {
+        ParseError { line: 1, column, message: message.to_string() }
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(scan("1,-2, +3", |s| s.integers::<i32>(",")), Ok(vec!(1, -2, 3)));
+        assert_eq!(scan("1,x", |s| s.integers::<i32>(",")), Err(error(3, "expected a number, found \"x\"")));
+        assert_eq!(scan("300", |s| s.integer::<u8>()),
+                   Err(error(1, "invalid number \"300\": number too large to fit in target type")));
+        assert_eq!(scan("12 ", |s| s.integer::<u8>()), Err(error(3, "unexpected \" \"")));
+    }
+
+    #[test]
+    fn choices() {
+        let directions = [("U", (0, 1)), ("D", (0, -1))];
+        assert_eq!(scan("U", |s| s.one_of(&directions)), Ok((0, 1)));
+        assert_eq!(scan("L", |s| s.one_of(&directions)), Err(error(1, "expected one of \"U\", \"D\", found \"L\"")));
+    }
+
+    #[test]
+    fn vectors() {
+        assert_eq!(scan("<x=-1, y=0, z=2>", |s| s.vector::<i32>(&["x", "y", "z"])), Ok(vec!(-1, 0, 2)));
+        assert_eq!(scan("<x=-1, z=0>", |s| s.vector::<i32>(&["x", "y"])), Err(error(8, "expected \"y=\", found \"z=0>\"")));
+    }
+
+    #[test]
+    fn recipes() {
+        assert_eq!(scan("7 A, 1 B => 1 C", |s| s.recipe::<u64>()), Ok((vec!((7, "A"), (1, "B")), (1, "C"))));
+        assert_eq!(scan("7 A, 1 B -> 1 C", |s| s.recipe::<u64>()), Err(error(10, "expected \"=>\", found \"-> 1 C\"")));
+    }
+
+    #[test]
+    fn line_numbers() {
+        assert_eq!(integer_lines::<_, i64>("1\n\n-2\n".as_bytes()).unwrap(), vec!(1, -2));
+        let e = integer_lines::<_, i64>("1\n2\n3a".as_bytes()).unwrap_err();
+        assert_eq!(e.to_string(), "line 3, column 2: unexpected \"a\"");
+    }
+}
@@ -0,0 +1,134 @@
+use crate::intcode::{BufferedIO, IntCodeMachine};
+use std::{
+    panic,
+    sync::{mpsc::{channel, Receiver, Sender}, Arc, Mutex},
+    thread,
+};
+
+// Runs that take longer than this are taken to never halt
+const DEFAULT_CYCLE_BUDGET: usize = 10000000;
+
+type RunResult = Result<BufferedIO, String>;
+// Index of the input within its batch, the input itself, the cycle budget and where to send the final IO
+type Job = (usize, Vec<i64>, usize, Sender<(usize, RunResult)>);
+
+/* Keeps a machine per core loaded with the same program, each run resets the
+ * machine back to the pristine image rather than building a new machine and
+ * thread for it. Only suits programs whose outputs depend on their inputs alone.
+ */
+pub struct MachinePool {
+    jobs: Option<Sender<Job>>,
+    workers: Vec<thread::JoinHandle<()>>,
+    // Runs still going after this many cycles are reported as failures
+    pub(crate) cycle_budget: usize,
+}
+
+impl MachinePool {
+    pub fn new(program: &[i64]) -> Self {
+        let size = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_size(program, size)
+    }
+
+    pub fn with_size(program: &[i64], size: usize) -> Self {
+        let (tx, rx) = channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+        let workers = (0..size.max(1)).map(|_| {
+            let rx = rx.clone();
+            let program = program.to_vec();
+            thread::spawn(move || worker(&program, rx))
+        }).collect();
+        Self { jobs: Some(tx), workers, cycle_budget: DEFAULT_CYCLE_BUDGET }
+    }
+
+    // Hands back the IO of each run in the same order as the inputs, so the
+    // outputs as well as any input that was left over. Fails if any of the
+    // runs panicked or didn't halt within the cycle budget.
+    pub fn run_batch(&self, inputs: Vec<Vec<i64>>) -> Result<Vec<BufferedIO>, String> {
+        let (tx, rx) = channel();
+        let num_inputs = inputs.len();
+        let jobs = self.jobs.as_ref().unwrap();
+        for (i, input) in inputs.into_iter().enumerate() {
+            jobs.send((i, input, self.cycle_budget, tx.clone())).map_err(|_| "Machine pool has shut down")?;
+        }
+        drop(tx);
+
+        let mut outputs = vec![BufferedIO::new(&[]); num_inputs];
+        for (i, io) in rx.iter() {
+            outputs[i] = io?;
+        }
+        Ok(outputs)
+    }
+}
+
+impl Drop for MachinePool {
+    fn drop(&mut self) {
+        // Closing the job channel lets the workers finish
+        self.jobs = None;
+        for w in self.workers.drain(..) {
+            let _ = w.join();
+        }
+    }
+}
+
+fn worker(program: &[i64], jobs: Arc<Mutex<Receiver<Job>>>) {
+    let mut machine = IntCodeMachine::new(program, BufferedIO::new(&[]));
+    let pristine = machine.save_state();
+
+    loop {
+        let job = jobs.lock().unwrap().recv();
+        let (i, input, cycle_budget, results) = match job {
+            Ok(j) => j,
+            Err(_) => break,
+        };
+
+        machine.restore_state(&pristine);
+        machine.io = BufferedIO::new(&input);
+        let halted = panic::catch_unwind(panic::AssertUnwindSafe(|| machine.run_for(cycle_budget)));
+        let output = match halted {
+            Ok(true) => Ok(machine.io.clone()),
+            Ok(false) => Err(format!("Run {} of the batch didn't halt within {} cycles", i + 1, cycle_budget)),
+            Err(_) => Err(format!("Intcode machine panicked on run {} of the batch", i + 1)),
+        };
+        let _ = results.send((i, output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads two numbers and outputs their sum
+    const ADD: [i64; 14] = [3, 11, 3, 12, 1, 11, 12, 13, 4, 13, 99, 0, 0, 0];
+
+    #[test]
+    fn runs_in_order() {
+        let pool = MachinePool::with_size(&ADD, 2);
+        let outputs = pool.run_batch(vec!(vec!(1, 2), vec!(3, 4), vec!(5, 6), vec!(1, 2))).unwrap();
+        let sums: Vec<_> = outputs.iter().map(|io| io.output.clone()).collect();
+        assert_eq!(sums, vec!(vec!(3), vec!(7), vec!(11), vec!(3)));
+        assert!(outputs.iter().all(|io| !io.starved && io.input.is_empty()));
+    }
+
+    #[test]
+    fn reports_bad_inputs() {
+        let pool = MachinePool::with_size(&ADD, 2);
+        let outputs = pool.run_batch(vec!(vec!(1), vec!(1, 2, 3))).unwrap();
+        assert!(outputs[0].starved);
+        assert_eq!(outputs[1].input, vec!(3));
+        assert_eq!(outputs[1].output, vec!(3));
+    }
+
+    #[test]
+    fn reports_failed_runs() {
+        // Writes to a negative address
+        let pool = MachinePool::with_size(&[1101, 0, 0, -1, 99], 2);
+        assert_eq!(pool.run_batch(vec!(vec!())).err(), Some(String::from("Intcode machine panicked on run 1 of the batch")));
+
+        // Loops forever once the input is 0
+        let mut pool = MachinePool::with_size(&[3, 9, 1005, 9, 8, 1105, 1, 5, 99, 0], 1);
+        pool.cycle_budget = 100;
+        assert_eq!(pool.run_batch(vec!(vec!(1), vec!(0))).err(), Some(String::from("Run 2 of the batch didn't halt within 100 cycles")));
+        // The pool keeps working afterwards
+        assert_eq!(pool.run_batch(vec!(vec!(1))).unwrap()[0].output, vec!());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    bench::Bencher,
+    solver::{input_file, DayResult, Options, SolveError, Solver, Stage, DEFAULT_YEAR},
+};
+use std::fs;
+
+// A solver with its input and output types erased, the answers only come back through Display
+pub trait Puzzle {
+    fn run(&self, day: i32, options: &Options) -> Result<DayResult, SolveError>;
+    fn bench(&self, day: i32, year: i32, bencher: &mut Bencher) -> Result<(), SolveError>;
+    fn interactive(&self) -> bool;
+    fn second_part(&self) -> bool;
+    fn play(&self, day: i32, options: &Options) -> Result<(), SolveError>;
+}
+
+impl<S: Solver> Puzzle for S {
+    fn run(&self, day: i32, options: &Options) -> Result<DayResult, SolveError> {
+        self.solve(day, options)
+    }
+
+    fn interactive(&self) -> bool {
+        S::INTERACTIVE
+    }
+
+    fn second_part(&self) -> bool {
+        S::SECOND_PART
+    }
+
+    // A session plays through part 1, the only part an interactive day has had so far
+    fn play(&self, day: i32, options: &Options) -> Result<(), SolveError> {
+        let input = self.load_input(options.year, day, &options.input)?;
+        Solver::play(self, &input).map_err(|e| SolveError::new(day, Stage::Part1, e))
+    }
+
+    // The input is read into memory first so parsing isn't timed along with the disk
+    fn bench(&self, day: i32, year: i32, bencher: &mut Bencher) -> Result<(), SolveError> {
+        let name = format!("{}/day{:02}", year, day);
+        let names = ["parse", "part1", "part2"].map(|stage| format!("{}/{}", name, stage));
+        if !names.iter().any(|n| bencher.wants(n)) {
+            return Ok(());
+        }
+
+        let path = input_file(year, day);
+        let bytes = fs::read(&path)
+            .map_err(|e| SolveError::new(day, Stage::Load, format!("Unable to open input file {}: {}", path, e)))?;
+        bencher.bench(&names[0], || self.parse_input(&bytes[..]));
+        let input = self.parse_input(&bytes[..]).map_err(|e| SolveError::new(day, Stage::Parse, e))?;
+        bencher.bench(&names[1], || self.solve_first(&input));
+        if S::SECOND_PART {
+            bencher.bench(&names[2], || self.solve_second(&input));
+        }
+        Ok(())
+    }
+}
+
+/* Each solution registers itself next to its Problem with
+ *     inventory::submit! {
+ *         Registration { year: 2019, day: 1, title: "...", tags: &[], solver: &Problem }
+ *     }
+ */
+pub struct Registration {
+    pub year: i32,
+    pub day: i32,
+    pub title: &'static str,
+    pub tags: &'static [&'static str],
+    pub solver: &'static (dyn Puzzle + Sync),
+}
+
+inventory::collect!(Registration);
+
+impl Registration {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+
+    pub fn run(&self, options: &Options) -> Result<DayResult, SolveError> {
+        self.solver.run(self.day, options)
+    }
+}
+
+// Everything that has been registered, ordered by year and day
+pub fn all() -> Vec<&'static Registration> {
+    let mut registrations: Vec<_> = inventory::iter::<Registration>.into_iter().collect();
+    registrations.sort_by_key(|r| (r.year, r.day));
+    registrations
+}
+
+pub fn for_year(year: i32) -> Vec<&'static Registration> {
+    all().into_iter().filter(|r| r.year == year).collect()
+}
+
+pub fn find(year: i32, day: i32) -> Option<&'static Registration> {
+    inventory::iter::<Registration>.into_iter().find(|r| r.year == year && r.day == day)
+}
+
+pub fn with_tag(year: i32, tag: &str) -> Vec<&'static Registration> {
+    for_year(year).into_iter().filter(|r| r.has_tag(tag)).collect()
+}
+
+// Runs the given day of options.year
+pub fn run_day(day: i32, options: &Options) -> Result<DayResult, SolveError> {
+    match find(options.year, day) {
+        Some(r) => r.run(options),
+        None if options.year == DEFAULT_YEAR => Err(SolveError::new(day, Stage::Lookup, "hasn't been attempted yet!")),
+        None => Err(SolveError::new(day, Stage::Lookup, format!("of {} hasn't been attempted yet!", options.year))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_day_registered_once() {
+        let days: Vec<i32> = for_year(2019).iter().map(|r| r.day).collect();
+        assert_eq!(days, (1..=25).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn tags() {
+        let intcode: Vec<i32> = with_tag(2019, "intcode").iter().map(|r| r.day).collect();
+        assert_eq!(intcode, vec!(2, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25));
+        assert!(with_tag(2019, "not a tag").is_empty());
+        // There was no event before 2015
+        assert!(with_tag(2014, "intcode").is_empty());
+    }
+}
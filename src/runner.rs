@@ -0,0 +1,62 @@
+use crate::{
+    registry::run_day,
+    solver::{DayResult, Options, SolveError},
+};
+use std::{
+    collections::BTreeMap,
+    sync::{atomic::{AtomicUsize, Ordering}, mpsc::channel},
+    thread,
+};
+
+pub type DayOutcome = (i32, Result<DayResult, SolveError>);
+
+// 0 asks for a thread per core
+pub fn num_threads(jobs: usize) -> usize {
+    match jobs {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        j => j,
+    }
+}
+
+/* Runs the days on up to `jobs` threads. However they finish, on_result sees
+ * each of them in the same order as the days so the output reads the same as
+ * running them one after the other.
+ */
+pub fn run_days(days: &[i32], options: &Options, jobs: usize, mut on_result: impl FnMut(&DayOutcome)) -> Vec<DayOutcome> {
+    let jobs = num_threads(jobs).min(days.len());
+    if jobs <= 1 {
+        return days.iter().map(|&day| {
+            let outcome = (day, run_day(day, options));
+            on_result(&outcome);
+            outcome
+        }).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = channel();
+    thread::scope(|s| {
+        for _ in 0..jobs {
+            let (tx, next) = (tx.clone(), &next);
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                match days.get(i) {
+                    Some(&day) => { let _ = tx.send((i, (day, run_day(day, options)))); },
+                    None => break,
+                }
+            });
+        }
+        drop(tx);
+
+        // Hold on to the days that finish early until the ones before them are done
+        let mut finished = BTreeMap::new();
+        let mut outcomes = vec!();
+        for (i, outcome) in rx.iter() {
+            finished.insert(i, outcome);
+            while let Some(outcome) = finished.remove(&outcomes.len()) {
+                on_result(&outcome);
+                outcomes.push(outcome);
+            }
+        }
+        outcomes
+    })
+}
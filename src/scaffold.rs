@@ -0,0 +1,401 @@
+use crate::solver::{input_file, DEFAULT_YEAR};
+use std::{
+    fs,
+    io,
+    path::Path,
+};
+
+const USAGE: &str = "\
+Usage: new <DAY> [OPTIONS]
+
+Creates src/solutions/dayNN.rs from a template, declares it in
+src/solutions/mod.rs and creates an empty input file for it. Days of other
+years go in src/solutions/yYYYY/ and read input/YYYY/dayNN.txt.
+
+Options:
+  --year <YEAR>        2019 by default
+  --template <NAME>    plain, grid or intcode, plain by default
+  --title <TITLE>      the title of the puzzle
+  --tags <TAGS>        comma separated tags, grid and intcode get theirs already
+  --force              overwrite the module if it already exists";
+
+const PLAIN: &str = r#"use crate::registry::Registration;
+use crate::solver::{self, Solver};
+use std::io::{BufRead, BufReader, Read};
+
+pub struct Problem;
+
+inventory::submit! {
+    Registration { year: $YEAR$, day: $DAY$, title: $TITLE$, tags: &[$TAGS$], solver: &Problem }
+}
+
+impl Solver for Problem {
+    type Input = Vec<String>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(BufReader::new(f).lines().collect::<Result<_, _>>()?)
+    }
+
+    fn solve_first(&self, _input: &Self::Input) -> solver::Result<Self::Output1> {
+        Err("Not solved yet".into())
+    }
+
+    fn solve_second(&self, _input: &Self::Input) -> solver::Result<Self::Output2> {
+        Err("Not solved yet".into())
+    }
+}
+"#;
+
+const GRID: &str = r#"use crate::{
+    registry::Registration,
+    solver::{self, Solver},
+    grid::Grid,
+};
+use std::{
+    convert::TryFrom,
+    io::Read,
+};
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub enum Tile {
+    #[default]
+    Empty,
+    Wall,
+}
+
+impl TryFrom<u8> for Tile {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'.' => Ok(Tile::Empty),
+            b'#' => Ok(Tile::Wall),
+            b => Err(format!("Invalid tile {:?}", b as char)),
+        }
+    }
+}
+
+pub struct Problem;
+
+inventory::submit! {
+    Registration { year: $YEAR$, day: $DAY$, title: $TITLE$, tags: &[$TAGS$], solver: &Problem }
+}
+
+impl Solver for Problem {
+    type Input = Grid<Tile>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(Grid::from_reader(f)?)
+    }
+
+    fn solve_first(&self, _input: &Self::Input) -> solver::Result<Self::Output1> {
+        Err("Not solved yet".into())
+    }
+
+    fn solve_second(&self, _input: &Self::Input) -> solver::Result<Self::Output2> {
+        Err("Not solved yet".into())
+    }
+}
+"#;
+
+const INTCODE: &str = r#"use crate::{
+    intcode::{parse_program, BufferedIO, IntCodeMachine},
+    registry::Registration,
+    solver::{self, Solver},
+};
+use std::io::Read;
+
+pub struct Problem;
+
+inventory::submit! {
+    Registration { year: $YEAR$, day: $DAY$, title: $TITLE$, tags: &[$TAGS$], solver: &Problem }
+}
+
+impl Solver for Problem {
+    type Input = Vec<i64>;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
+    }
+
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
+        let mut machine = IntCodeMachine::new(input, BufferedIO::new(&[]));
+        machine.run();
+        Err("Not solved yet".into())
+    }
+
+    fn solve_second(&self, _input: &Self::Input) -> solver::Result<Self::Output2> {
+        Err("Not solved yet".into())
+    }
+}
+"#;
+
+// Every template ends with the same tests, ignored until they are filled in with the examples
+const TESTS: &str = r#"
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input_for, check_second};
+
+    #[test]
+    #[ignore = "no examples yet"]
+    fn first_examples() {
+        check_first(&Problem, &[]);
+    }
+
+    #[test]
+    #[ignore = "no examples yet"]
+    fn second_examples() {
+        check_second(&Problem, &[]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input_for(&Problem, $YEAR$, $DAY$);
+    }
+}
+"#;
+
+fn module_name(day: i32) -> String {
+    format!("day{:02}", day)
+}
+
+/* Adds "mod <name>;" among the other module declarations of mod.rs, keeping
+ * them in order. Returns false if it was already declared.
+ */
+fn declare_module(mod_rs: &Path, name: &str, header: &str) -> io::Result<bool> {
+    let contents = match fs::read_to_string(mod_rs) {
+        Ok(c) => c,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::from(header),
+        Err(e) => return Err(e),
+    };
+    // Some declarations are pub(crate) or the like
+    let declared = |l: &str| l.trim_end_matches(';').rsplit(' ').next().map(String::from);
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let modules: Vec<(usize, String)> = lines.iter().enumerate()
+        .filter(|(_, l)| l.contains("mod ") && l.ends_with(';') && !l.starts_with("//"))
+        .filter_map(|(i, l)| declared(l).map(|m| (i, m)))
+        .collect();
+    if modules.iter().any(|(_, m)| m == name) {
+        return Ok(false);
+    }
+
+    let position = match modules.iter().find(|(_, m)| m.as_str() > name) {
+        // Keep any comment about the next module with it
+        Some((i, _)) => {
+            let mut i = *i;
+            while i > modules[0].0 + 1 && lines[i - 1].starts_with("//") {
+                i -= 1;
+            }
+            i
+        },
+        None => modules.last().map_or(lines.len(), |(i, _)| i + 1),
+    };
+    lines.insert(position, format!("mod {};", name));
+    fs::write(mod_rs, lines.join("\n") + "\n")?;
+    Ok(true)
+}
+
+fn write_error(path: &Path, e: io::Error) -> String {
+    format!("Unable to write {}: {}", path.display(), e)
+}
+
+fn render(year: i32, day: i32, template: &str, title: &str, tags: &[String]) -> Result<String, String> {
+    let source = match template {
+        "plain" => PLAIN,
+        "grid" => GRID,
+        "intcode" => INTCODE,
+        t => return Err(format!("Unknown template {}, expected plain, grid or intcode", t)),
+    };
+
+    let mut all_tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+    if template != "plain" && !all_tags.contains(&template) {
+        all_tags.insert(0, template);
+    }
+    Ok((String::from(source) + TESTS)
+        .replace("$YEAR$", &year.to_string())
+        .replace("$DAY$", &day.to_string())
+        .replace("$TITLE$", &format!("{:?}", title))
+        .replace("$TAGS$", &all_tags.iter().map(|t| format!("{:?}", t)).collect::<Vec<_>>().join(", ")))
+}
+
+// Writes the module of the day under the solutions directory and declares it
+fn write_module(solutions: &Path, year: i32, day: i32, code: &str, force: bool) -> Result<(), String> {
+    let dir = if year == DEFAULT_YEAR { solutions.to_path_buf() } else { solutions.join(format!("y{}", year)) };
+    let module = dir.join(format!("{}.rs", module_name(day)));
+    if module.exists() && !force {
+        return Err(format!("{} already exists, use --force to overwrite it", module.display()));
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| write_error(&dir, e))?;
+    fs::write(&module, code).map_err(|e| write_error(&module, e))?;
+    println!("Created {}", module.display());
+
+    let mut header = String::new();
+    if year != DEFAULT_YEAR {
+        header = format!("// The days of {}, each registers itself with crate::registry\n", year);
+        let mod_rs = solutions.join("mod.rs");
+        if declare_module(&mod_rs, &format!("y{}", year), "").map_err(|e| write_error(&mod_rs, e))? {
+            println!("Declared y{} in {}", year, mod_rs.display());
+        }
+    }
+    let mod_rs = dir.join("mod.rs");
+    if declare_module(&mod_rs, &module_name(day), &header).map_err(|e| write_error(&mod_rs, e))? {
+        println!("Declared {} in {}", module_name(day), mod_rs.display());
+    }
+    Ok(())
+}
+
+fn generate(year: i32, day: i32, template: &str, title: &str, tags: &[String], force: bool) -> Result<(), String> {
+    let code = render(year, day, template, title, tags)?;
+    // The sources of this crate, wherever it is run from
+    write_module(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src/solutions"), year, day, &code, force)?;
+
+    let input = input_file(year, day);
+    let input = Path::new(&input);
+    if !input.exists() {
+        if let Some(parent) = input.parent() {
+            fs::create_dir_all(parent).map_err(|e| write_error(parent, e))?;
+        }
+        fs::write(input, "").map_err(|e| write_error(input, e))?;
+        println!("Created {}, paste the puzzle input in there", input.display());
+    }
+    Ok(())
+}
+
+pub fn run(args: &[String]) {
+    let mut day = None;
+    let mut year = DEFAULT_YEAR;
+    let mut template = String::from("plain");
+    let mut title = None;
+    let mut tags = vec!();
+    let mut force = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().cloned().ok_or(format!("Missing value for {}", name));
+        let parsed = match arg.as_str() {
+            "-h" | "--help" => { println!("{}", USAGE); return; },
+            "--year" => value("--year").and_then(|y| y.parse().map_err(|_| format!("Invalid year {}", y))).map(|y| year = y),
+            "--template" => value("--template").map(|t| template = t),
+            "--title" => value("--title").map(|t| title = Some(t)),
+            "--tags" => value("--tags").map(|t| tags = t.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()),
+            "--force" => { force = true; Ok(()) },
+            a if a.starts_with('-') => Err(format!("Unknown option {}", a)),
+            a if day.is_none() => a.parse().map(|d| day = Some(d)).map_err(|_| format!("Invalid day {}", a)),
+            a => Err(format!("Unexpected argument {}", a)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n\n{}", e, USAGE);
+            return;
+        }
+    }
+
+    let day = match day {
+        Some(d) if (1..=25).contains(&d) => d,
+        Some(d) => { println!("Invalid day {}, expected 1 to 25\n\n{}", d, USAGE); return; },
+        None => { println!("{}", USAGE); return; },
+    };
+    let title = title.unwrap_or_else(|| format!("Day {}", day));
+    if let Err(e) = generate(year, day, &template, &title, &tags, force) {
+        println!("{}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process::{self, Command}};
+
+    #[test]
+    fn renders_templates() {
+        let tags = vec!(String::from("bfs"));
+        for (template, tags_line, input) in [
+            ("plain", r#"tags: &["bfs"]"#, "type Input = Vec<String>;"),
+            ("grid", r#"tags: &["grid", "bfs"]"#, "type Input = Grid<Tile>;"),
+            ("intcode", r#"tags: &["intcode", "bfs"]"#, "type Input = Vec<i64>;"),
+        ] {
+            let code = render(2018, 9, template, "Sensor \"Boost\"", &tags).unwrap();
+            let registration = format!(r#"    Registration {{ year: 2018, day: 9, title: "Sensor \"Boost\"", {}, solver: &Problem }}"#, tags_line);
+            assert!(code.lines().any(|l| l == registration), "{} registers the day as\n{}", template, code);
+            assert!(code.lines().any(|l| l.trim() == input), "{} has the wrong input", template);
+            assert!(code.lines().any(|l| l.trim() == "check_full_input_for(&Problem, 2018, 9);"));
+            assert_eq!(code.matches("#[ignore").count(), 3);
+            assert!(!code.contains('$'), "{} left a placeholder", template);
+        }
+        assert!(render(2019, 9, "fancy", "", &[]).is_err());
+    }
+
+    fn copy_dir(from: &Path, to: &Path) {
+        fs::create_dir_all(to).unwrap();
+        for entry in fs::read_dir(from).unwrap().map(Result::unwrap) {
+            let target = to.join(entry.file_name());
+            if entry.path().is_dir() {
+                copy_dir(&entry.path(), &target);
+            } else {
+                fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    // Type checks a day of each template in a copy of the crate
+    #[test]
+    #[ignore = "slow, builds a copy of the crate"]
+    fn templates_compile() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let copy = env::temp_dir().join(format!("aoc_scaffold_crate_{}", process::id()));
+        let _ = fs::remove_dir_all(&copy);
+        copy_dir(&manifest_dir.join("src"), &copy.join("src"));
+        for file in ["Cargo.toml", "Cargo.lock"] {
+            let _ = fs::copy(manifest_dir.join(file), copy.join(file));
+        }
+        for (day, template) in [(1, "plain"), (2, "grid"), (3, "intcode")] {
+            let code = render(2018, day, template, "Test", &[]).unwrap();
+            write_module(&copy.join("src/solutions"), 2018, day, &code, false).unwrap();
+        }
+
+        let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+        let status = Command::new(cargo)
+            .args(["check", "--offline", "--bins", "--tests", "--manifest-path"])
+            .arg(copy.join("Cargo.toml"))
+            .env("CARGO_TARGET_DIR", manifest_dir.join("target/scaffold-check"))
+            .status()
+            .unwrap();
+        fs::remove_dir_all(&copy).unwrap();
+        assert!(status.success(), "The scaffolded days don't compile");
+    }
+
+    #[test]
+    fn declares_modules() {
+        let solutions = env::temp_dir().join(format!("aoc_scaffold_{}", process::id()));
+        let _ = fs::remove_dir_all(&solutions);
+        fs::create_dir_all(&solutions).unwrap();
+        let mod_rs = solutions.join("mod.rs");
+        fs::write(&mod_rs, "// The days\nmod day01;\n// Slow\nmod day05;\n").unwrap();
+
+        write_module(&solutions, 2019, 3, "// day 3\n", false).unwrap();
+        write_module(&solutions, 2019, 7, "// day 7\n", false).unwrap();
+        assert_eq!(fs::read_to_string(&mod_rs).unwrap(), "// The days\nmod day01;\nmod day03;\n// Slow\nmod day05;\nmod day07;\n");
+        assert_eq!(fs::read_to_string(solutions.join("day03.rs")).unwrap(), "// day 3\n");
+
+        assert!(write_module(&solutions, 2019, 3, "// again\n", false).is_err());
+        write_module(&solutions, 2019, 3, "// again\n", true).unwrap();
+        assert_eq!(fs::read_to_string(solutions.join("day03.rs")).unwrap(), "// again\n");
+        assert_eq!(fs::read_to_string(&mod_rs).unwrap().matches("mod day03;").count(), 1);
+
+        write_module(&solutions, 2018, 2, "// 2018 day 2\n", false).unwrap();
+        assert!(fs::read_to_string(&mod_rs).unwrap().ends_with("mod day07;\nmod y2018;\n"));
+        assert_eq!(fs::read_to_string(solutions.join("y2018/mod.rs")).unwrap(),
+                   "// The days of 2018, each registers itself with crate::registry\nmod day02;\n");
+        assert!(solutions.join("y2018/day02.rs").exists());
+
+        fs::remove_dir_all(&solutions).unwrap();
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    intcode::{parse_program, IntCodeMachine, MachineState, IO, MEMORY_LIMIT},
+    solver::{input_file, DEFAULT_YEAR},
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{self, BufRead, BufReader, ErrorKind, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7019";
+const DEFAULT_RUN_CYCLES: usize = 100000000;
+
+// Queues up input until the machine asks for it, an empty queue blocks the
+// machine instead of halting it so that a client can send more later
+pub struct RemoteIO {
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
+}
+
+impl IO for RemoteIO {
+    fn get(&mut self) -> io::Result<i64> {
+        self.input.pop_front().ok_or_else(|| io::Error::new(ErrorKind::WouldBlock, "Waiting for input"))
+    }
+
+    fn put(&mut self, val: i64) -> io::Result<()> {
+        self.output.push_back(val);
+        Ok(())
+    }
+}
+
+/* Line based protocol, every request gets a single line back starting with
+ * either "OK" or "ERR":
+ *
+ *   LOAD <day> | LOAD <a,b,c,..>   load a day's program or an inline one
+ *   STEP [n]                       execute n instructions (default 1)
+ *   RUN [max cycles]               run until halted or blocked on input
+ *   INPUT <v> [v ..]               queue input values
+ *   ASCII <text>                   queue text followed by a newline
+ *   OUTPUT                         take all pending output values
+ *   PEEK <addr> [len]              read memory
+ *   POKE <addr> <value>            write memory
+ *   SNAPSHOT [name]                save the machine state
+ *   RESTORE [name]                 go back to a saved state
+ *   STATUS                         pc, cycles and whether halted or blocked
+ *   QUIT                           close the connection
+ */
+pub struct Session {
+    machine: Option<IntCodeMachine<RemoteIO>>,
+    snapshots: HashMap<String, MachineState>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self { machine: None, snapshots: HashMap::new() }
+    }
+
+    pub fn load(&mut self, program: &[i64]) {
+        let io = RemoteIO { input: VecDeque::new(), output: VecDeque::new() };
+        self.machine = Some(IntCodeMachine::new(program, io));
+        self.snapshots.clear();
+    }
+
+    pub fn handle(&mut self, line: &str) -> String {
+        let line = line.trim();
+        let (command, rest) = match line.find(' ') {
+            Some(i) => (&line[..i], line[i + 1..].trim()),
+            None => (line, ""),
+        };
+        let args: Vec<&str> = rest.split_whitespace().collect();
+
+        match self.execute(&command.to_uppercase(), rest, &args) {
+            Ok(response) if response.is_empty() => String::from("OK"),
+            Ok(response) => format!("OK {}", response),
+            Err(e) => format!("ERR {}", e),
+        }
+    }
+
+    fn execute(&mut self, command: &str, rest: &str, args: &[&str]) -> Result<String, String> {
+        if command == "LOAD" {
+            let program = if rest.contains(',') {
+                parse_values(rest)?
+            } else {
+                let day: i32 = parse_arg(args.first(), "day")?;
+                File::open(input_file(DEFAULT_YEAR, day))
+                    .and_then(parse_program)
+                    .map_err(|e| format!("{}: {}", input_file(DEFAULT_YEAR, day), e))?
+            };
+            self.load(&program);
+            return Ok(format!("{}", program.len()));
+        }
+
+        let machine = self.machine.as_mut().ok_or("No program loaded")?;
+        match command {
+            "STEP" => {
+                let steps = args.first().map_or(Ok(1), |_| parse_arg(args.first(), "count"))?;
+                guarded(machine, |m| {
+                    for _ in 0..steps {
+                        if m.halted() {
+                            break;
+                        }
+                        m.step();
+                    }
+                })
+            },
+            "RUN" => {
+                let max_cycles = args.first().map_or(Ok(DEFAULT_RUN_CYCLES), |_| parse_arg(args.first(), "cycles"))?;
+                guarded(machine, |m| { m.run_for(max_cycles); })
+            },
+            "INPUT" => {
+                let values = args.iter().map(|v| parse_arg(Some(v), "value")).collect::<Result<Vec<i64>, _>>()?;
+                machine.io.input.extend(values);
+                Ok(format!("{}", machine.io.input.len()))
+            },
+            "ASCII" => {
+                machine.io.input.extend(rest.chars().map(|c| c as i64));
+                machine.io.input.push_back(10);
+                Ok(format!("{}", machine.io.input.len()))
+            },
+            "OUTPUT" => {
+                let values: Vec<String> = machine.io.output.drain(..).map(|v| v.to_string()).collect();
+                Ok(values.join(" "))
+            },
+            "PEEK" => {
+                let address: usize = parse_arg(args.first(), "address")?;
+                let len: usize = args.get(1).map_or(Ok(1), |_| parse_arg(args.get(1), "length"))?;
+                let size = machine.program.len();
+                if address >= size {
+                    return Err(format!("Address {} is outside the memory of {} values", address, size));
+                }
+                let end = address.checked_add(len).map_or(size, |e| e.min(size));
+                let values: Vec<String> = machine.program[address..end].iter().map(|v| v.to_string()).collect();
+                Ok(values.join(" "))
+            },
+            "POKE" => {
+                let address: usize = parse_arg(args.first(), "address")?;
+                let value: i64 = parse_arg(args.get(1), "value")?;
+                if address >= MEMORY_LIMIT {
+                    return Err(format!("Address {} is past the memory limit of {} values", address, MEMORY_LIMIT));
+                }
+                if address >= machine.program.len() {
+                    machine.program.resize(address + 1, 0);
+                }
+                machine.program[address] = value;
+                Ok(String::new())
+            },
+            "SNAPSHOT" => {
+                let name = args.first().unwrap_or(&"default").to_string();
+                self.snapshots.insert(name.clone(), machine.save_state());
+                Ok(name)
+            },
+            "RESTORE" => {
+                let name = args.first().unwrap_or(&"default");
+                let state = self.snapshots.get(*name).ok_or(format!("No snapshot named {}", name))?;
+                machine.restore_state(state);
+                Ok(status(machine))
+            },
+            "STATUS" => Ok(status(machine)),
+            c => Err(format!("Unknown command {}", c)),
+        }
+    }
+}
+
+// Bad programs panic on invalid opcodes, bad addresses or by running off the end, that shouldn't take down the server
+fn guarded<F>(machine: &mut IntCodeMachine<RemoteIO>, f: F) -> Result<String, String>
+    where F: FnOnce(&mut IntCodeMachine<RemoteIO>)
+{
+    panic::catch_unwind(AssertUnwindSafe(|| f(machine))).map_err(|e| {
+        let message = e.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| e.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown error"));
+        format!("Machine crashed at pc={}: {}", machine.pc(), message)
+    })?;
+    Ok(status(machine))
+}
+
+fn status(machine: &IntCodeMachine<RemoteIO>) -> String {
+    format!("pc={} cycles={} halted={} blocked={} output={}",
+            machine.pc(), machine.cycles(), machine.halted(), machine.blocked(), machine.io.output.len())
+}
+
+fn parse_arg<T: std::str::FromStr>(arg: Option<&&str>, name: &str) -> Result<T, String> {
+    let arg = arg.ok_or(format!("Missing {}", name))?;
+    arg.parse().map_err(|_| format!("Invalid {} {}", name, arg))
+}
+
+fn parse_values(s: &str) -> Result<Vec<i64>, String> {
+    s.split(',').map(|v| v.trim().parse().map_err(|_| format!("Invalid value {}", v.trim()))).collect()
+}
+
+pub struct Server {
+    listener: TcpListener,
+    session: Session,
+}
+
+impl Server {
+    pub fn bind(address: &str) -> io::Result<Self> {
+        Ok(Self { listener: TcpListener::bind(address)?, session: Session::new() })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Clients are served one at a time, the machine carries over between them
+    pub fn serve(&mut self) -> io::Result<()> {
+        loop {
+            let (stream, address) = self.listener.accept()?;
+            if let Err(e) = self.serve_client(stream) {
+                println!("Lost connection to {}: {}", address, e);
+            }
+        }
+    }
+
+    pub fn serve_client(&mut self, stream: TcpStream) -> io::Result<()> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().eq_ignore_ascii_case("QUIT") {
+                writeln!(writer, "OK")?;
+                break;
+            }
+            writeln!(writer, "{}", self.session.handle(&line))?;
+        }
+        Ok(())
+    }
+}
+
+// Usage: serve [address] [day]
+pub fn run(args: &[String]) {
+    let address = args.first().map_or(DEFAULT_ADDRESS, String::as_str);
+    let mut server = match Server::bind(address) {
+        Ok(s) => s,
+        Err(e) => { println!("Unable to listen on {}: {}", address, e); return; }
+    };
+    if let Some(day) = args.get(1) {
+        println!("{}", server.session.handle(&format!("LOAD {}", day)));
+    }
+    match server.local_addr() {
+        Ok(a) => println!("Listening on {}", a),
+        Err(e) => println!("Unable to get the listening address: {}", e),
+    }
+    if let Err(e) = server.serve() {
+        println!("Server stopped: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl Client {
+        fn connect(address: SocketAddr) -> Self {
+            let stream = TcpStream::connect(address).unwrap();
+            Self { writer: stream.try_clone().unwrap(), reader: BufReader::new(stream) }
+        }
+
+        fn send(&mut self, command: &str) -> String {
+            writeln!(self.writer, "{}", command).unwrap();
+            let mut response = String::new();
+            self.reader.read_line(&mut response).unwrap();
+            response.trim_end().to_string()
+        }
+    }
+
+    fn start_server() -> SocketAddr {
+        let mut server = Server::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.serve());
+        address
+    }
+
+    #[test]
+    fn echo_program_blocks_until_input() {
+        let mut client = Client::connect(start_server());
+        // Reads a value, outputs it and jumps back to the start
+        assert_eq!(client.send("LOAD 3,9,4,9,1105,1,0,99,0,0"), "OK 10");
+        assert_eq!(client.send("RUN"), "OK pc=0 cycles=1 halted=false blocked=true output=0");
+        assert_eq!(client.send("INPUT 42 7"), "OK 2");
+        client.send("RUN");
+        assert_eq!(client.send("OUTPUT"), "OK 42 7");
+        assert_eq!(client.send("OUTPUT"), "OK");
+        assert_eq!(client.send("QUIT"), "OK");
+    }
+
+    #[test]
+    fn step_peek_and_poke() {
+        let mut client = Client::connect(start_server());
+        assert_eq!(client.send("STEP"), "ERR No program loaded");
+        client.send("LOAD 1,0,0,0,99");
+        assert_eq!(client.send("STEP"), "OK pc=4 cycles=1 halted=false blocked=false output=0");
+        assert_eq!(client.send("PEEK 0 2"), "OK 2 0");
+        assert_eq!(client.send("POKE 8 5"), "OK");
+        assert_eq!(client.send("PEEK 7 3"), "OK 0 5");
+        assert_eq!(client.send("STEP 5"), "OK pc=4 cycles=2 halted=true blocked=false output=0");
+        assert_eq!(client.send("FROB"), "ERR Unknown command FROB");
+    }
+
+    #[test]
+    fn errors_on_bad_memory_access() {
+        let mut client = Client::connect(start_server());
+        client.send("LOAD 1,0,0,0,99");
+        assert_eq!(client.send("PEEK 3 18446744073709551615"), "OK 0 99");
+        assert_eq!(client.send("PEEK 5"), "ERR Address 5 is outside the memory of 5 values");
+        assert_eq!(client.send("PEEK 18446744073709551615 2"), "ERR Address 18446744073709551615 is outside the memory of 5 values");
+        assert_eq!(client.send("POKE 1048576 1"), "ERR Address 1048576 is past the memory limit of 1048576 values");
+        assert_eq!(client.send("POKE 18446744073709551615 1"), "ERR Address 18446744073709551615 is past the memory limit of 1048576 values");
+        assert_eq!(client.send("POKE -1 1"), "ERR Invalid address -1");
+        assert_eq!(client.send("STATUS"), "OK pc=0 cycles=0 halted=false blocked=false output=0");
+    }
+
+    #[test]
+    fn errors_on_crashes() {
+        let mut client = Client::connect(start_server());
+        client.send("LOAD 42,99");
+        assert_eq!(client.send("STEP"), "ERR Machine crashed at pc=0: Invalid opcode 42!");
+        // Runs off the end of the program without halting
+        client.send("LOAD 1,0,0,0");
+        assert!(client.send("RUN").starts_with("ERR Machine crashed at pc=4: "));
+        // Writes to an address far too large to allocate
+        client.send("LOAD 1101,0,0,1000000000000,99");
+        assert_eq!(client.send("RUN"), "ERR Machine crashed at pc=0: Address 1000000000000 is past the memory limit of 1048576 values!");
+        client.send("LOAD 1101,0,0,-1,99");
+        assert_eq!(client.send("RUN"), "ERR Machine crashed at pc=0: Negative address -1!");
+        // The session is still usable afterwards
+        client.send("LOAD 1,0,0,0,99");
+        assert_eq!(client.send("RUN"), "OK pc=4 cycles=2 halted=true blocked=false output=0");
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut client = Client::connect(start_server());
+        client.send("LOAD 1,0,0,0,99");
+        assert_eq!(client.send("SNAPSHOT start"), "OK start");
+        client.send("RUN");
+        assert_eq!(client.send("PEEK 0"), "OK 2");
+        assert_eq!(client.send("RESTORE start"), "OK pc=0 cycles=0 halted=false blocked=false output=0");
+        assert_eq!(client.send("PEEK 0"), "OK 1");
+        assert_eq!(client.send("RESTORE nope"), "ERR No snapshot named nope");
+    }
+
+    #[test]
+    fn machine_survives_reconnects() {
+        let address = start_server();
+        let mut client = Client::connect(address);
+        client.send("LOAD 3,9,4,9,1105,1,0,99,0,0");
+        client.send("ASCII hi");
+        client.send("QUIT");
+
+        let mut client = Client::connect(address);
+        client.send("RUN");
+        assert_eq!(client.send("OUTPUT"), "OK 104 105 10");
+    }
+}
@@ -1,27 +1,33 @@
-use crate::solver::Solver;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
 };
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 1, title: "The Tyranny of the Rocket Equation", tags: &[], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<u64>;
     type Output1 = u64;
     type Output2 = u64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        let f = BufReader::new(f);
-        f.lines().flatten().flat_map(|l| l.parse()).collect()
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        BufReader::new(f)
+            .lines()
+            .map(|l| -> solver::Result<u64> { Ok(l?.parse()?) })
+            .collect()
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
-        input.iter().cloned().map(calc_fuel_needed).sum()
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
+        Ok(input.iter().cloned().map(calc_fuel_needed).sum())
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
-        input.iter().cloned().map(total_fuel_mass).sum()
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
+        Ok(input.iter().cloned().map(total_fuel_mass).sum())
     }
 }
 
@@ -43,3 +49,25 @@ fn total_fuel_mass(mass: u64) -> u64 {
     }
     fuel_required
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[("12", 2), ("14", 2), ("1969", 654), ("100756", 33583)]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[("14", 2), ("1969", 966), ("100756", 50346)]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 1);
+    }
+}
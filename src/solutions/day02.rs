@@ -1,28 +1,33 @@
 use crate::intcode::{parse_program, IntCodeMachine, NoIO};
-use crate::solver::Solver;
-use std::fs::File;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
+use std::io::Read;
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 2, title: "1202 Program Alarm", tags: &["intcode"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = i64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut program = input.clone();
         program[1] = 12;
         program[2] = 2;
         let mut machine = IntCodeMachine::new(&program, NoIO {});
         machine.run();
-        machine.program[0]
+        Ok(machine.program[0])
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         for i in 0..99 {
             for j in 0..99 {
                 let mut program = input.clone();
@@ -31,10 +36,37 @@ impl Solver for Problem {
                 let mut machine = IntCodeMachine::new(&program, NoIO {});
                 machine.run();
                 if machine.program[0] == 19690720 {
-                    return 100 * i + j;
+                    return Ok(100 * i + j);
                 }
             }
         }
-        panic!("Should not get here.");
+        Err("No noun and verb produce 19690720".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    fn run(program: &str) -> Vec<i64> {
+        let mut machine = IntCodeMachine::new(&Problem.parse_str(program).unwrap(), NoIO {});
+        machine.run();
+        machine.program
+    }
+
+    #[test]
+    fn machine_examples() {
+        assert_eq!(run("1,9,10,3,2,3,11,0,99,30,40,50"), vec!(3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50));
+        assert_eq!(run("1,0,0,0,99"), vec!(2, 0, 0, 0, 99));
+        assert_eq!(run("2,3,0,3,99"), vec!(2, 3, 0, 6, 99));
+        assert_eq!(run("2,4,4,5,99,0"), vec!(2, 4, 4, 5, 99, 9801));
+        assert_eq!(run("1,1,1,4,99,5,6,0,99"), vec!(30, 1, 1, 4, 2, 5, 6, 0, 99));
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 2);
     }
 }
@@ -1,8 +1,9 @@
-use crate::solver::Solver;
+use crate::parse;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::convert::TryInto;
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+    io::Read,
 };
 
 #[derive(Clone, Copy, Debug, Eq)]
@@ -19,44 +20,44 @@ impl PartialEq for Point {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 3, title: "Crossed Wires", tags: &[], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = (Vec<Point>, Vec<Point>);
     type Output1 = u32;
     type Output2 = u32;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        let f = BufReader::new(f);
-        let mut f = f.lines();
-        let wire_movements1: Vec<String> = f.next().unwrap().unwrap().split(',').map(|s| s.to_string()).collect();
-        let wire_movements2: Vec<String> = f.next().unwrap().unwrap().split(',').map(|s| s.to_string()).collect();
-        (process_wire_movements(&wire_movements1), process_wire_movements(&wire_movements2))
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        let wires = parse::lines(f, |s| s.separated(",", |s| {
+            let direction = s.one_of(&[("U", (0, 1)), ("D", (0, -1)), ("L", (-1, 0)), ("R", (1, 0))])?;
+            Ok((direction, s.integer()?))
+        }))?;
+        match wires.as_slice() {
+            [wire1, wire2] => Ok((process_wire_movements(wire1), process_wire_movements(wire2))),
+            _ => Err(format!("Expected two wires, got {}", wires.len()).into()),
+        }
     }
 
-    fn solve_first(&self, (points_set1, points_set2): &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, (points_set1, points_set2): &Self::Input) -> solver::Result<Self::Output1> {
         let intersections: Vec<&Point> = points_set1.iter().filter(|i| points_set2.contains(&i)).collect();
-        intersections.iter().map(|p| calculate_manhattan_distance(&p)).min().unwrap()
+        Ok(intersections.iter().map(|p| calculate_manhattan_distance(&p)).min().ok_or("The wires never cross")?)
     }
 
-    fn solve_second(&self, (points_set1, points_set2): &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, (points_set1, points_set2): &Self::Input) -> solver::Result<Self::Output2> {
         let intersections: Vec<&Point> = points_set1.iter().filter(|i| points_set2.contains(&i)).collect();
-        intersections.iter().map(|p| {
+        Ok(intersections.iter().map(|p| {
             points_set1.iter().position(|x| *x == **p).unwrap() + points_set2.iter().position(|y| *y == **p).unwrap() + 2
-        }).min().unwrap() as Self::Output2
+        }).min().ok_or("The wires never cross")? as Self::Output2)
     }
 }
 
-fn process_wire_movements(movements: &Vec<String>) -> Vec<Point> {
+// Each movement is the direction to move in and how many steps to take
+fn process_wire_movements(movements: &[((i32, i32), u32)]) -> Vec<Point> {
     let mut points: Vec<Point> = vec![];
     let mut curr_point: Point = Point { x: 0, y: 0 };
-    for m in movements {
-        let move_vector = match &m[..1] {
-            "U" => (0, 1),
-            "D" => (0, -1),
-            "L" => (-1, 0),
-            "R" => (1, 0),
-            _ => panic!("Invalid movement!")
-        };
-        let steps = m[1..].parse().unwrap();
+    for &(move_vector, steps) in movements {
         for _ in 0..steps {
             curr_point.x += move_vector.0;
             curr_point.y += move_vector.1;
@@ -69,3 +70,31 @@ fn process_wire_movements(movements: &Vec<String>) -> Vec<Point> {
 fn calculate_manhattan_distance(p: &Point) -> u32 {
     (p.x.abs() + p.y.abs()).try_into().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    const EXAMPLES: [&str; 3] = [
+        "R8,U5,L5,D3\nU7,R6,D4,L4",
+        "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83",
+        "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+    ];
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[(EXAMPLES[0], 6), (EXAMPLES[1], 159), (EXAMPLES[2], 135)]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[(EXAMPLES[0], 30), (EXAMPLES[1], 610), (EXAMPLES[2], 410)]);
+    }
+
+    #[test]
+    #[ignore = "slow, needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 3);
+    }
+}
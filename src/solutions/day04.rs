@@ -1,19 +1,26 @@
-use crate::solver::Solver;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::convert::TryInto;
 use std::{
-    fs::File,
+    io::Read,
 };
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 4, title: "Secure Container", tags: &[], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = (u32, u32);
     type Output1 = u32;
     type Output2 = u32;
 
-    fn parse_input(&self, _: File) -> Self::Input {
-        let range_start = 357253;
-        let range_end = 892942;
+    fn parse_input<R: Read>(&self, mut f: R) -> solver::Result<Self::Input> {
+        let mut range = String::new();
+        f.read_to_string(&mut range)?;
+        let (range_start, range_end) = range.trim().split_once('-').ok_or("Expected a range like 111111-222222")?;
+        let (range_start, range_end): (u32, u32) = (range_start.parse()?, range_end.parse()?);
         let mut match1 = 0;
         let mut match2 = 0;
 
@@ -24,7 +31,7 @@ impl Solver for Problem {
             }).collect();
             let ascending = num_vec.windows(2).all(|w| w[0] <= w[1]);
             if ascending {
-                let collapsed_vec: Vec<&[u32]> = num_vec.group_by(|a, b| a == b).collect();
+                let collapsed_vec: Vec<&[u32]> = num_vec.chunk_by(|a, b| a == b).collect();
                 if collapsed_vec.iter().any(|&i| i.len() >= 2) {
                     match1 += 1;
                 }
@@ -34,14 +41,37 @@ impl Solver for Problem {
             }
         }
 
-        (match1, match2)
+        Ok((match1, match2))
+    }
+
+    fn solve_first(&self, (answer, _): &Self::Input) -> solver::Result<Self::Output1> {
+        Ok(*answer)
+    }
+
+    fn solve_second(&self, (_, answer): &Self::Input) -> solver::Result<Self::Output2> {
+        Ok(*answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    // The examples are single passwords, so each is given as a range of one
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[("111111-111111", 1), ("223450-223450", 0), ("123789-123789", 0)]);
     }
 
-    fn solve_first(&self, (answer, _): &Self::Input) -> Self::Output1 {
-        *answer
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[("112233-112233", 1), ("123444-123444", 0), ("111122-111122", 1)]);
     }
 
-    fn solve_second(&self, (_, answer): &Self::Input) -> Self::Output2 {
-        *answer
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 4);
     }
 }
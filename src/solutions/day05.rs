@@ -1,6 +1,7 @@
 use crate::intcode::{parse_program, IntCodeMachine, IO};
-use crate::solver::Solver;
-use std::fs::File;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
+use std::io::Read;
 use std::io::Result;
 
 pub struct SimpleIO {
@@ -20,24 +21,55 @@ impl IO for SimpleIO {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 5, title: "Sunny with a Chance of Asteroids", tags: &["intcode"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = i64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut machine = IntCodeMachine::new(&input, SimpleIO { val: 1 });
         machine.run();
-        machine.io.val
+        Ok(machine.io.val)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut machine = IntCodeMachine::new(&input, SimpleIO { val: 5 });
         machine.run();
-        machine.io.val
+        Ok(machine.io.val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    const EQUAL_TO_8: &str = "3,9,8,9,10,9,4,9,99,-1,8";
+    const LESS_THAN_8: &str = "3,3,1107,-1,8,3,4,3,99";
+    const COMPARE_TO_8: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+    // Part 1 runs the programs with 1 as the input and part 2 with 5
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[(EQUAL_TO_8, 0), (LESS_THAN_8, 1), (COMPARE_TO_8, 999)]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[(EQUAL_TO_8, 0), (LESS_THAN_8, 1), (COMPARE_TO_8, 999)]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 5);
     }
 }
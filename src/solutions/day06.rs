@@ -1,8 +1,9 @@
-use crate::solver::Solver;
+use crate::parse;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::{
-    fs::File,
     collections::{HashMap, HashSet},
-    io::{BufRead, BufReader},
+    io::Read,
 };
 
 pub struct SpaceObject {
@@ -12,18 +13,23 @@ pub struct SpaceObject {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 6, title: "Universal Orbit Map", tags: &["graph"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = HashMap<String, SpaceObject>;
     type Output1 = u32;
     type Output2 = u32;
 
-    fn parse_input(&self, f: File) -> Self::Input {
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
         let mut satellite_map: HashMap<String, SpaceObject> = HashMap::new();
-        for line in BufReader::new(f).lines() {
-            let orbit_string = String::from(&line.unwrap());
-            let mut split = orbit_string.split(')');
-            let space_obj: String = split.next().unwrap().to_string();
-            let orbiter: String = split.next().unwrap().to_string();
+        let orbits = parse::lines(f, |s| {
+            let space_obj = s.word()?.to_string();
+            s.expect(")")?;
+            Ok((space_obj, s.word()?.to_string()))
+        })?;
+        for (space_obj, orbiter) in orbits {
             let map_match = satellite_map.get_mut(&orbiter);
             match map_match {
                 Some(orbiter_object) => { orbiter_object.parent_object.insert_str(0, &space_obj); },
@@ -35,14 +41,14 @@ impl Solver for Problem {
                 parent_object: String::new(), satellites: vec!()});
             new_space_obj.satellites.push(String::from(&orbiter));
         }
-        satellite_map
+        Ok(satellite_map)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
-        total_direct_and_indirect_orbits(input, "COM", 0)
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
+        Ok(total_direct_and_indirect_orbits(input, "COM", 0))
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut seen: HashSet<&str> = HashSet::new();
         let mut from: HashMap<&str, &str> = HashMap::new();
         let mut to_visit: Vec<&str> = vec!("YOU");
@@ -55,7 +61,7 @@ impl Solver for Problem {
             if curr == "SAN" {
                 break;
             }
-            let curr_object = input.get(curr).unwrap();
+            let curr_object = input.get(curr).ok_or_else(|| format!("Unknown object {}", curr))?;
             curr_object.satellites.iter().for_each(|x| {
                 if !seen.contains(x.as_str()) {
                     seen.insert(x.as_str());
@@ -73,14 +79,14 @@ impl Solver for Problem {
         let mut curr = "SAN";
         let mut num_nodes = 0;
         loop {
-            curr = from.get(curr).unwrap();
+            curr = from.get(curr).ok_or("There's no path from YOU to SAN")?;
             if curr == "YOU" {
                 break;
             }
             num_nodes += 1;
         }
 
-        num_nodes - 1 // We count the "SAN" node
+        Ok(num_nodes - 1) // We count the "SAN" node
     }
 }
 
@@ -99,3 +105,27 @@ fn total_direct_and_indirect_orbits(satellite_map: &HashMap<String, SpaceObject>
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    const ORBITS: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[(ORBITS, 42)]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[(&format!("{}\nK)YOU\nI)SAN", ORBITS), 4)]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 6);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::intcode::{parse_program, IntCodeMachine, AsyncIO, Connector};
-use crate::solver::Solver;
-use std::fs::File;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
+use std::io::Read;
 use itertools::Itertools;
 use std::{
     iter::from_fn,
@@ -10,29 +11,33 @@ use std::{
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 7, title: "Amplification Circuit", tags: &["intcode"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = i64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         (0..5)
             .permutations(5)
             .map(|phases| run_with_phases(input, &phases))
             .max()
-            .unwrap()
+            .ok_or_else(|| "No phase settings to try".into())
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         (5..10)
             .permutations(5)
             .map(|phases| run_with_phases_async(input, &phases))
             .max()
-            .unwrap()
+            .ok_or_else(|| "No phase settings to try".into())
     }
 }
 
@@ -106,3 +111,32 @@ fn run_with_phases_async(program: &Vec<i64>, phases: &[i64]) -> i64 {
     // wait for final output value
     output_thread.join().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[
+            ("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0", 43210),
+            ("3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0", 54321),
+            ("3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0", 65210),
+        ]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[
+            ("3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5", 139629729),
+            ("3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,-5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10", 18216),
+        ]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 7);
+    }
+}
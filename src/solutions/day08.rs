@@ -1,46 +1,58 @@
-use crate::solver::Solver;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::{
-    fs::File,
     io::Read
 };
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 8, title: "Space Image Format", tags: &[], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<u8>;
     type Output1 = usize;
     type Output2 = String;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        f.bytes().flatten().map(|b| b - b'0').collect()
+    fn parse_input<R: Read>(&self, mut f: R) -> solver::Result<Self::Input> {
+        let mut contents = String::new();
+        f.read_to_string(&mut contents)?;
+        contents.trim()
+            .bytes()
+            .map(|b| match b {
+                b'0'..=b'9' => Ok(b - b'0'),
+                b => Err(format!("Invalid pixel {:?}", b as char).into()),
+            })
+            .collect()
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let image = Image::from_array(input, 25, 6);
-        // Find the layer with the least zeroes
-        let (layer_with_least_zero, _) = image.layers
-            .iter()
-            .enumerate()
-            .map(|(i, v)| (i, v.iter().filter(|x| **x == 0).count()))
-            .min_by_key(|(_, c)| *c)
-            .unwrap();
-        // Get number of ones and twoes
-        let ones = image.layers[layer_with_least_zero as usize].iter().filter(|x| **x == 1).count();
-        let twos = image.layers[layer_with_least_zero as usize].iter().filter(|x| **x == 2).count();
-        ones * twos
+        checksum(&image)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let image = Image::from_array(input, 25, 6);
-        // Rasterize the image so that we can print it easily
-        let rasterized_image = image.rasterize();
-        for line in rasterized_image {
-            println!("{}", line);
-        }
-        String::from("YLFPJ")
+        // The message is the image itself, as it was rendered
+        Ok(image.rasterize().join("\n"))
     }
 }
 
+fn checksum(image: &Image) -> solver::Result<usize> {
+    // Find the layer with the least zeroes
+    let (layer_with_least_zero, _) = image.layers
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (i, v.iter().filter(|x| **x == 0).count()))
+        .min_by_key(|(_, c)| *c)
+        .ok_or("The image has no layers")?;
+    // Get number of ones and twoes
+    let ones = image.layers[layer_with_least_zero as usize].iter().filter(|x| **x == 1).count();
+    let twos = image.layers[layer_with_least_zero as usize].iter().filter(|x| **x == 2).count();
+    Ok(ones * twos)
+}
+
 struct Image {
     layers: Vec<Vec<u8>>,
     width: usize,
@@ -50,11 +62,11 @@ struct Image {
 impl Image {
     fn from_array(input: &[u8], width: usize, height: usize) -> Self {
         let layer_size = width * height;
-        let mut layers: Vec<Vec<u8>> = input
-            .chunks(layer_size)
+        // Anything past the last full layer isn't part of the image
+        let layers: Vec<Vec<u8>> = input
+            .chunks_exact(layer_size)
             .map(|l| l.into())
             .collect();
-        layers.pop();
         Self { layers, width, height }
     }
 
@@ -70,3 +82,29 @@ impl Image {
         flat_image.as_bytes().chunks(self.width).map(|x| String::from(std::str::from_utf8(x).ok().unwrap())).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    // The examples use smaller images than the 25x6 of the puzzle
+    #[test]
+    fn checksum_example() {
+        let image = Image::from_array(&Problem.parse_str("123456789012").unwrap(), 3, 2);
+        assert_eq!(image.layers.len(), 2);
+        assert_eq!(checksum(&image).unwrap(), 1);
+    }
+
+    #[test]
+    fn rasterize_example() {
+        let image = Image::from_array(&Problem.parse_str("0222112222120000").unwrap(), 2, 2);
+        assert_eq!(image.rasterize(), vec!(" 1", "1 "));
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 8);
+    }
+}
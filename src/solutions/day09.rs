@@ -1,6 +1,7 @@
 use crate::intcode::{parse_program, IntCodeMachine, IO};
-use crate::solver::Solver;
-use std::fs::File;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
+use std::io::Read;
 use std::io::Result;
 
 pub struct SimpleIO {
@@ -20,24 +21,59 @@ impl IO for SimpleIO {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 9, title: "Sensor Boost", tags: &["intcode"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = i64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut machine = IntCodeMachine::new(&input, SimpleIO { val: 1 });
         machine.run();
-        machine.io.val
+        Ok(machine.io.val)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut machine = IntCodeMachine::new(&input, SimpleIO { val: 2 });
         machine.run();
-        machine.io.val
+        Ok(machine.io.val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::BufferedIO;
+    use crate::testing::{check_first, check_full_input};
+
+    const QUINE: &str = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+
+    #[test]
+    fn quine_example() {
+        let program = Problem.parse_str(QUINE).unwrap();
+        let mut machine = IntCodeMachine::new(&program, BufferedIO::new(&[]));
+        machine.run();
+        assert_eq!(machine.io.output, program);
+    }
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[
+            ("1102,34915192,34915192,7,4,7,99,0", 1219070632396864),
+            ("104,1125899906842624,99", 1125899906842624),
+        ]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 9);
     }
 }
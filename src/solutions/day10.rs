@@ -1,11 +1,12 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     grid::{Grid, GridPoint, Vector2D}
 };
 use std::{
     collections::HashMap,
     convert::TryFrom,
-    fs::File,
+    io::Read,
 };
 
 #[derive(Clone, Eq, PartialEq)]
@@ -34,25 +35,29 @@ impl Default for Elem {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 10, title: "Monitoring Station", tags: &["grid"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Grid<Elem>;
     type Output1 = usize;
     type Output2 = u64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        Grid::from_reader(f).unwrap()
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(Grid::from_reader(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let (_, number) = find_best_location(input);
-        number
+        Ok(number)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let (point, _) = find_best_location(input);
         let vaporisation_order = calculate_vaporisation_order(&point, input);
-        let target = &vaporisation_order[199];
-        (target.x as u64 * 100) + target.y as u64
+        let target = vaporisation_order.get(199).ok_or("Fewer than 200 asteroids get vaporised")?;
+        Ok((target.x as u64 * 100) + target.y as u64)
     }
 }
 
@@ -124,3 +129,48 @@ fn calculate_vaporisation_order(from: &GridPoint, grid: &Grid<Elem>) -> Vec<Grid
 
     vaporisation_order
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    const SMALL: &str = ".#..#\n.....\n#####\n....#\n...##";
+    const LARGE: &str = "\
+.#..##.###...#######
+##.############..##.
+.#.######.########.#
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##";
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[(SMALL, 8), (LARGE, 210)]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[(LARGE, 802)]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 10);
+    }
+}
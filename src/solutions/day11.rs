@@ -1,11 +1,13 @@
 use crate::{
     intcode::{parse_program, IntCodeMachine, AsyncIO},
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     grid::Point,
+    visualize::{self, Cell, Frame, Rgb},
 };
 use std::{
     collections::HashMap,
-    fs::File,
+    io::Read,
     thread,
     error::Error,
     sync::mpsc::{Sender, Receiver}
@@ -13,16 +15,20 @@ use std::{
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 11, title: "Space Police", tags: &["intcode", "grid"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = usize;
     type Output2 = String;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut board = Board::new();
         let mut painting_robot = PaintingRobot::new(input);
 
@@ -30,13 +36,14 @@ impl Solver for Problem {
             if painting_robot.paint(&mut board).is_err() {
                 break;
             }
+            visualize::frame(|| board.frame(Some(painting_robot.position)));
         }
 
         painting_robot.wait();
-        board.panels.len()
+        Ok(board.panels.len())
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut board = Board::new();
         let mut painting_robot = PaintingRobot::new(input);
         board.paint(&Point{ x: 0, y: 0 }, &PaintColour::White);
@@ -45,12 +52,11 @@ impl Solver for Problem {
             if painting_robot.paint(&mut board).is_err() {
                 break;
             }
+            visualize::frame(|| board.frame(Some(painting_robot.position)));
         }
 
         painting_robot.wait();
-        board.print();
-
-        String::from("URCAFLCP")
+        Ok(board.render())
     }
 }
 
@@ -76,10 +82,10 @@ impl PaintColour {
         }
     }
 
-    fn to_char(&self) -> char {
+    fn cell(&self) -> Cell {
         match self {
-            PaintColour::Black => ' ',
-            PaintColour::White => '#',
+            PaintColour::Black => Cell::BLANK,
+            PaintColour::White => Cell::new('#', Rgb::WHITE),
         }
     }
 }
@@ -123,18 +129,20 @@ impl Board {
         *self.panels.get(p).unwrap_or(&PaintColour::Black)
     }
 
-    fn print(&self) {
-        let min_x = self.panels.keys().min_by_key(|p| p.x).unwrap().x;
-        let min_y = self.panels.keys().min_by_key(|p| p.y).unwrap().y;
-        let max_x = self.panels.keys().max_by_key(|p| p.x).unwrap().x;
-        let max_y = self.panels.keys().max_by_key(|p| p.y).unwrap().y;
-
-        for y in (min_y..=max_y).rev() {
-            for x in min_x..=max_x {
-                print!("{}", self.colour(&Point{ x: x, y: y }).to_char());
+    // The panels painted so far, y grows upwards here
+    fn frame(&self, robot: Option<Point>) -> Frame {
+        Frame::from_points(&self.panels, |p, _| {
+            if Some(p) == robot {
+                Cell::new('R', Rgb::RED)
+            } else {
+                self.colour(&p).cell()
             }
-            println!();
-        }
+        }).flipped()
+    }
+
+    // The registration as it was painted, one line per row
+    fn render(&self) -> String {
+        self.frame(None).to_text()
     }
 }
 
@@ -202,3 +210,15 @@ impl PaintingRobot {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 11);
+    }
+}
@@ -1,37 +1,41 @@
-use crate::solver::Solver;
+use crate::parse;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::{
-    io::{BufReader,BufRead},
-    fs::File,
+    io::Read,
     cmp::Ordering
 };
-use regex::Regex;
 use itertools::Itertools;
 use num::Integer;
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 12, title: "The N-Body Problem", tags: &["math"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = JupiterSystem;
     type Output1 = u64;
     type Output2 = u64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        JupiterSystem::from_file(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        JupiterSystem::from_reader(f)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut jupiter_copy = input.clone();
         for _ in 0..1000 {
             jupiter_copy.run_simulation_step();
         }
-        jupiter_copy.calculate_energy()
+        Ok(jupiter_copy.calculate_energy())
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let x_period = find_position_period(&mut input.moons.iter().map(|m| m.position[0]).collect());
         let y_period = find_position_period(&mut input.moons.iter().map(|m| m.position[1]).collect());
         let z_period = find_position_period(&mut input.moons.iter().map(|m| m.position[2]).collect());
-        x_period.lcm(&y_period.lcm(&z_period))
+        Ok(x_period.lcm(&y_period.lcm(&z_period)))
     }
 }
 
@@ -60,20 +64,12 @@ pub struct JupiterSystem {
 }
 
 impl JupiterSystem {
-    fn from_file(f: File) -> Self {
-        let r = Regex::new(r"<x=(-?\d+), y=(-?\d+), z=(-?\d+)").unwrap();
-        let moons = BufReader::new(f)
-            .lines()
-            .map(|l| {
-                let line = l.unwrap();
-                let caps = r.captures(&line).unwrap();
-                let x = caps.get(1).unwrap().as_str().parse().unwrap();
-                let y = caps.get(2).unwrap().as_str().parse().unwrap();
-                let z = caps.get(3).unwrap().as_str().parse().unwrap();
-                Moon { position: [x, y, z], velocity: [0, 0, 0] }
-            })
-            .collect();
-        JupiterSystem { moons }
+    fn from_reader<R: Read>(f: R) -> solver::Result<Self> {
+        let moons = parse::lines(f, |s| {
+            let position = s.vector(&["x", "y", "z"])?;
+            Ok(Moon { position: [position[0], position[1], position[2]], velocity: [0, 0, 0] })
+        })?;
+        Ok(JupiterSystem { moons })
     }
 
     fn run_simulation_step(&mut self) {
@@ -137,3 +133,35 @@ fn find_position_period(positions: &mut Vec<i32>) -> u64 {
     // Steps is half the period, e.g. half of sine wave
     steps * 2
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_full_input, check_second};
+
+    const FIRST: &str = "<x=-1, y=0, z=2>\n<x=2, y=-10, z=-7>\n<x=4, y=-8, z=8>\n<x=3, y=5, z=-1>";
+    const SECOND: &str = "<x=-8, y=-10, z=0>\n<x=5, y=5, z=10>\n<x=2, y=-7, z=3>\n<x=9, y=-8, z=-3>";
+
+    // The examples run for fewer steps than part 1 does
+    #[test]
+    fn energy_examples() {
+        for (input, steps, energy) in [(FIRST, 10, 179), (SECOND, 100, 1940)] {
+            let mut jupiter = Problem.parse_str(input).unwrap();
+            for _ in 0..steps {
+                jupiter.run_simulation_step();
+            }
+            assert_eq!(jupiter.calculate_energy(), energy);
+        }
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[(FIRST, 2772), (SECOND, 4686774924)]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 12);
+    }
+}
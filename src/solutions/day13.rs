@@ -1,11 +1,13 @@
 use crate::{
     intcode::{parse_program, IntCodeMachine, AsyncIO},
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     grid::Point,
+    visualize::{self, Cell, Frame, Rgb},
 };
 use std::{
     collections::HashMap,
-    fs::File,
+    io::Read,
     sync::mpsc::{Sender, Receiver},
     thread,
     error::Error
@@ -13,28 +15,32 @@ use std::{
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 13, title: "Care Package", tags: &["intcode", "grid"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = usize;
     type Output2 = u64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut area = GameArea::new();
         let mut arcade_cabinet = ArcadeCabinet::new(input);
 
         let _ = arcade_cabinet.only_fill_map(&mut area);
 
         arcade_cabinet.wait();
-        area.print();
+        visualize::frame(|| area.frame());
 
-        area.tiles.values().filter(|t| **t == Tile::Block).count()
+        Ok(area.tiles.values().filter(|t| **t == Tile::Block).count())
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut area = GameArea::new();
         let mut play_for_free_input = input.clone();
         play_for_free_input[0] = 2;
@@ -46,7 +52,7 @@ impl Solver for Problem {
 
         arcade_cabinet.wait();
 
-        end_score
+        Ok(end_score)
     }
 }
 
@@ -71,13 +77,13 @@ impl Tile {
         }
     }
 
-    fn to_char(&self) -> char {
+    fn cell(&self) -> Cell {
         match self {
-            Tile::Empty => ' ',
-            Tile::Wall => '#',
-            Tile::Block => '@',
-            Tile::HorizontalPaddle => '_',
-            Tile::Ball => 'o'
+            Tile::Empty => Cell::BLANK,
+            Tile::Wall => Cell::new('#', Rgb::GREY),
+            Tile::Block => Cell::new('@', Rgb::BLUE),
+            Tile::HorizontalPaddle => Cell::new('_', Rgb::WHITE),
+            Tile::Ball => Cell::new('o', Rgb::YELLOW)
         }
     }
 }
@@ -91,27 +97,13 @@ impl GameArea {
         Self { tiles: HashMap::new() }
     }
 
-    fn get_tile(&self, p: &Point) -> Option<&Tile> {
-        self.tiles.get(p)
-    }
-
     fn set_tile(&mut self, p: &Point, t: &Tile) {
         self.tiles.insert(p.clone(), t.clone());
     }
 
-    fn print(&self) {
-        let min_x = self.tiles.keys().min_by_key(|p| p.x).unwrap().x;
-        let min_y = self.tiles.keys().min_by_key(|p| p.y).unwrap().y;
-        let max_x = self.tiles.keys().max_by_key(|p| p.x).unwrap().x;
-        let max_y = self.tiles.keys().max_by_key(|p| p.y).unwrap().y;
-
-        // Don't reverse it since Y is from distance from top
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                print!("{}", self.get_tile(&Point{ x: x, y: y }).unwrap().to_char());
-            }
-            println!();
-        }
+    // Don't flip it since Y is from distance from top
+    fn frame(&self) -> Frame {
+        Frame::from_points(&self.tiles, |_, t| t.unwrap_or(&Tile::Empty).cell())
     }
 }
 
@@ -168,7 +160,7 @@ impl ArcadeCabinet {
             area.set_tile(&Point{ x, y }, &tile);
 
             match &tile {
-                Tile::Ball => { self.ball = Some(x) },
+                Tile::Ball => { self.ball = Some(x); visualize::frame(|| area.frame()) },
                 Tile::HorizontalPaddle => {
                     if self.paddle == None {
                         self.paddle = Some(x);
@@ -205,3 +197,15 @@ impl ArcadeCabinet {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 13);
+    }
+}
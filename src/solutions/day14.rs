@@ -1,59 +1,50 @@
-use crate::solver::Solver;
+use crate::parse;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::{
-    io::{BufRead, BufReader},
+    io::Read,
     collections::{HashMap, VecDeque},
-    fs::File,
     fmt
 };
-use regex::Regex;
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 14, title: "Space Stoichiometry", tags: &["graph"], solver: &Problem }
+}
+
 impl Solver for Problem {
     // No references instead of string since we can't use lifetimes
     type Input = HashMap<String, Recipe>;
     type Output1 = u64;
     type Output2 = u64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        let mut recipe_map = HashMap::new();
-        let r = Regex::new(r"(\d+) (\w+)").unwrap();
-
-        BufReader::new(f)
-            .lines()
-            .filter_map(|l| l.ok())
-            .for_each(|l| {
-                let splits: Vec<_> = l.split("=>").collect();
-                let product_caps = r.captures(&splits[1].trim()).unwrap();
-                let product_quantity: u64 = product_caps.get(1).unwrap().as_str().parse().unwrap();
-                let product_string = product_caps.get(2).unwrap().as_str();
-                let mut new_recipe = Recipe::new(product_string, product_quantity);
-                let input_splits: Vec<_> = splits[0].split(',').collect();
-                for i in input_splits {
-                    let input_caps = r.captures(i.trim()).unwrap();
-                    let input_quantity: u64 = input_caps.get(1).unwrap().as_str().parse().unwrap();
-                    let input_string = input_caps.get(2).unwrap().as_str();
-                    new_recipe.add_input(input_string, input_quantity);
-                }
-                recipe_map.insert(String::from(product_string), new_recipe);
-            });
-
-        recipe_map
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        let recipes = parse::lines(f, |s| {
+            let (inputs, (product_quantity, product_string)) = s.recipe()?;
+            let mut new_recipe = Recipe::new(product_string, product_quantity);
+            for (input_quantity, input_string) in inputs {
+                new_recipe.add_input(input_string, input_quantity);
+            }
+            Ok(new_recipe)
+        })?;
+
+        Ok(recipes.into_iter().map(|r| (r.output.clone(), r)).collect())
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut factory = Factory::new(input.clone());
         factory.build("FUEL", 1);
-        factory.ore_used
+        Ok(factory.ore_used)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut factory = Factory::new(input.clone());
         let mut fuel_made = 0;
         while factory.build("FUEL", 1) {
             fuel_made += 1;
         }
-        fuel_made
+        Ok(fuel_made)
     }
 }
 
@@ -151,3 +142,71 @@ impl Factory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    const FIRST: &str = "\
+10 ORE => 10 A
+1 ORE => 1 B
+7 A, 1 B => 1 C
+7 A, 1 C => 1 D
+7 A, 1 D => 1 E
+7 A, 1 E => 1 FUEL";
+    const SECOND: &str = "\
+9 ORE => 2 A
+8 ORE => 3 B
+7 ORE => 5 C
+3 A, 4 B => 1 AB
+5 B, 7 C => 1 BC
+4 C, 1 A => 1 CA
+2 AB, 3 BC, 4 CA => 1 FUEL";
+    const THIRD: &str = "\
+157 ORE => 5 NZVS
+165 ORE => 6 DCFZ
+44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
+12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
+179 ORE => 7 PSHF
+177 ORE => 5 HKGWZ
+7 DCFZ, 7 PSHF => 2 XJWVT
+165 ORE => 2 GPVTF
+3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT";
+    const LAST: &str = "\
+171 ORE => 8 CNZTR
+7 ZLQW, 3 BMBT, 9 XCVML, 26 XMNCP, 1 WPTQ, 2 MZWV, 1 RJRHP => 4 PLWSL
+114 ORE => 4 BHXH
+14 VRPVC => 6 BMBT
+6 BHXH, 18 KTJDG, 12 WPTQ, 7 PLWSL, 31 FHTLT, 37 ZDVW => 1 FUEL
+6 WPTQ, 2 BMBT, 8 ZLQW, 18 KTJDG, 1 XMNCP, 6 MZWV, 1 RJRHP => 6 FHTLT
+15 XDBXC, 2 LTCX, 1 VRPVC => 6 ZLQW
+13 WPTQ, 10 LTCX, 3 RJRHP, 14 XMNCP, 2 MZWV, 1 ZLQW => 1 ZDVW
+5 BMBT => 4 WPTQ
+189 ORE => 9 KTJDG
+1 MZWV, 17 XDBXC, 3 XCVML => 2 XMNCP
+12 VRPVC, 27 CNZTR => 2 XDBXC
+15 KTJDG, 12 BHXH => 5 XCVML
+3 BHXH, 2 VRPVC => 7 MZWV
+121 ORE => 7 VRPVC
+7 XCVML => 6 RJRHP
+5 BHXH, 4 VRPVC => 5 LTCX";
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[(FIRST, 31), (SECOND, 165), (THIRD, 13312), (LAST, 2210736)]);
+    }
+
+    // Part 2 builds one fuel at a time, the other examples make millions of them
+    #[test]
+    #[ignore = "slow"]
+    fn second_examples() {
+        check_second(&Problem, &[(LAST, 460664)]);
+    }
+
+    #[test]
+    #[ignore = "slow, needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 14);
+    }
+}
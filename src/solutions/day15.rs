@@ -1,11 +1,13 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     grid::Point,
-    intcode::{parse_program, AsyncIO, IntCodeMachine}
+    intcode::{parse_program, AsyncIO, IntCodeMachine},
+    visualize::{self, Cell, Frame, Rgb},
 };
 use std::{
     collections::{HashMap, VecDeque},
-    fs::File,
+    io::Read,
     thread,
     error::Error,
     sync::mpsc::{Sender, Receiver}
@@ -26,48 +28,52 @@ impl Default for Elem {
 }
 
 impl Elem {
-    fn to_char(&self) -> char {
+    fn cell(&self) -> Cell {
         match self {
-            Elem::Empty => '.',
-            Elem::Wall => '#',
-            Elem::OxygenSystem => '@',
-            Elem::Unknown => '?',
+            Elem::Empty => Cell::new('.', Rgb::GREY),
+            Elem::Wall => Cell::new('#', Rgb::WHITE),
+            Elem::OxygenSystem => Cell::new('@', Rgb::BLUE),
+            Elem::Unknown => Cell::BLANK,
         }
     }
 }
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 15, title: "Oxygen System", tags: &["intcode", "grid", "maze"], solver: &Problem }
+}
+
 impl Solver for Problem {
-    type Input = Navigator;
+    type Input = ShipMap;
     type Output1 = u64;
     type Output2 = u64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        let input = parse_program(f);
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        let input = parse_program(f)?;
         let mut navigator = Navigator::new(&input);
         let _ = navigator.explore_map();
-        navigator.print_map();
-        navigator
+        visualize::frame(|| navigator.frame());
+        Ok(navigator.into_map())
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
-        let mut curr_position = input.oxygen_position.unwrap();
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
+        let mut curr_position = input.oxygen_position.ok_or("The oxygen system was never found")?;
         let origin = Point { x: 0, y: 0 };
         let mut steps = 0;
         while !curr_position.eq(&origin) {
-            curr_position = *input.reachable_from.get(&curr_position).unwrap();
+            curr_position = *input.reachable_from.get(&curr_position).ok_or("The oxygen system isn't reachable")?;
             steps += 1;
         }
-        steps
+        Ok(steps)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         // Do BFS to find longest path from oxygen
         let mut distances: HashMap<Point, u64> = HashMap::new();
         let mut to_evaluate: VecDeque<Point> = VecDeque::new();
         let mut parent_map: HashMap<Point, Point>  = HashMap::new();
-        let starting_point = input.oxygen_position.unwrap();
+        let starting_point = input.oxygen_position.ok_or("The oxygen system was never found")?;
 
         to_evaluate.push_back(starting_point);
         distances.insert(starting_point, 0);
@@ -94,10 +100,17 @@ impl Solver for Problem {
             });
         }
 
-        *distances.values().max().unwrap()
+        Ok(*distances.values().max().unwrap())
     }
 }
 
+// What the droid found, without the machine it used to find it
+pub struct ShipMap {
+    reachable_from: HashMap<Point, Point>,
+    map: HashMap<Point, Elem>,
+    oxygen_position: Option<Point>,
+}
+
 pub struct Navigator {
     _handle: thread::JoinHandle<()>,
     robot_position: Point,
@@ -126,6 +139,10 @@ impl Navigator {
         }
     }
 
+    fn into_map(self) -> ShipMap {
+        ShipMap { reachable_from: self.reachable_from, map: self.map, oxygen_position: self.oxygen_position }
+    }
+
     fn explore_map(&mut self) -> Result<(), Box<dyn Error>> {
         self.reachable_from.insert(self.robot_position, self.robot_position);
         let mut to_visit: VecDeque<Point> = VecDeque::new();
@@ -156,6 +173,7 @@ impl Navigator {
                 _ => panic!("Invalid status code!")
             }
             self.add_visitable_neighbours(&mut to_visit);
+            visualize::frame(|| self.frame());
         }
 
         Ok(())
@@ -222,24 +240,17 @@ impl Navigator {
         })
     }
 
-    fn print_map(&self) {
-        let min_x = self.map.keys().min_by_key(|p| p.x).unwrap().x;
-        let min_y = self.map.keys().min_by_key(|p| p.y).unwrap().y;
-        let max_x = self.map.keys().max_by_key(|p| p.x).unwrap().x;
-        let max_y = self.map.keys().max_by_key(|p| p.y).unwrap().y;
-
-        for y in (min_y..=max_y).rev() {
-            for x in min_x..=max_x {
-                if self.robot_position.x == x && self.robot_position.y == y {
-                    print!("R");
-                } else if x == 0 && y == 0 {
-                    print!("O");
-                } else {
-                    print!("{}", self.map.get(&Point { x, y }).unwrap_or(&Elem::Unknown).to_char());
-                }
+    // What has been explored so far, y grows upwards here
+    fn frame(&self) -> Frame {
+        Frame::from_points(&self.map, |p, e| {
+            if p == self.robot_position {
+                Cell::new('R', Rgb::RED)
+            } else if p.x == 0 && p.y == 0 {
+                Cell::new('O', Rgb::GREEN)
+            } else {
+                e.unwrap_or(&Elem::Unknown).cell()
             }
-            println!();
-        }
+        }).flipped()
     }
 }
 
@@ -269,3 +280,15 @@ fn movement_from(from: &Point, to: &Point) -> i64 {
         _ => panic!("'from' {:?} and 'to' {:?} are not neighbours!", from, to)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    #[test]
+    #[ignore = "slow, needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 15);
+    }
+}
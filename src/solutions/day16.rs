@@ -1,48 +1,53 @@
-use crate::solver::Solver;
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
 };
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 16, title: "Flawed Frequency Transmission", tags: &[], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i16>;
     type Output1 = String;
     type Output2 = String;
 
-    fn parse_input(&self, f: File) -> Self::Input {
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
         BufReader::new(f)
             .lines()
-            .flatten()
-            .collect::<String>()
-            .as_bytes()
-            .iter()
-            .map(|s| (*s - 48) as i16) // '0'
+            .collect::<Result<String, _>>()?
+            .bytes()
+            .map(|s| match s {
+                b'0'..=b'9' => Ok((s - 48) as i16), // '0'
+                s => Err(format!("Invalid digit {:?}", s as char).into()),
+            })
             .collect()
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let output = fft(input);
         let first_eight = output.iter().take(8).map(|s| s.to_string()).collect();
-        first_eight
+        Ok(first_eight)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         /* Optimisation from
          * https://www.reddit.com/r/adventofcode/comments/ebf5cy/2019_day_16_part_2_understanding_how_to_come_up/fb4bvw4/
          */
         let real_signal = input.repeat(10000);
-        let message_offset = real_signal.iter().take(7).map(|s| s.to_string()).collect::<String>().parse().unwrap();
-        let mut real_signal = real_signal[message_offset..].to_vec();
+        let message_offset: usize = real_signal.iter().take(7).map(|s| s.to_string()).collect::<String>().parse()?;
+        let mut real_signal = real_signal.get(message_offset..).ok_or("The message offset is past the end of the signal")?.to_vec();
         let signal_length = real_signal.len();
         for _ in 0..100 {
-            for i in 2..signal_length {
+            for i in 2..=signal_length {
                 real_signal[signal_length - i] = (real_signal[signal_length - i] + real_signal[signal_length - i + 1]) % 10;
             }
         }
         let message = real_signal.iter().take(8).map(|s| s.to_string()).collect::<String>();
-        message
+        Ok(message)
     }
 }
 
@@ -80,3 +85,33 @@ fn construct_pattern(sequence_len: usize, repeat_by: usize) -> Vec<i16> {
     output.truncate(sequence_len);
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[
+            ("80871224585914546619083218645595", String::from("24176176")),
+            ("19617804207202209144916044189917", String::from("73745418")),
+            ("69317163492948606335995924319873", String::from("52432133")),
+        ]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[
+            ("03036732577212944063491565474664", String::from("84462026")),
+            ("02935109699940807407585447034323", String::from("78725270")),
+            ("03081770884921959731165446850517", String::from("53553731")),
+        ]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 16);
+    }
+}
@@ -1,11 +1,13 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     grid::Point,
-    intcode::{parse_program, AsyncIO, IntCodeMachine}
+    intcode::{parse_program, AsyncIO, IntCodeMachine},
+    visualize::{self, Cell, Frame, Rgb},
 };
 use std::{
     collections::HashMap,
-    fs::File,
+    io::Read,
     thread,
     error::Error,
     sync::mpsc::{Sender, Receiver}
@@ -13,22 +15,26 @@ use std::{
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 17, title: "Set and Forget", tags: &["intcode", "grid"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = u64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut scaffolding = Scaffolding::new(input);
         let _ = scaffolding.populate_grid();
-        //scaffolding.print();
+        visualize::frame(|| scaffolding.frame());
 
-        let max_x = scaffolding.grid.keys().max_by_key(|p| p.x).unwrap().x;
-        let max_y = scaffolding.grid.keys().max_by_key(|p| p.y).unwrap().y;
+        let max_x = scaffolding.grid.keys().max_by_key(|p| p.x).ok_or("The camera didn't output anything")?.x;
+        let max_y = scaffolding.grid.keys().max_by_key(|p| p.y).ok_or("The camera didn't output anything")?.y;
         let scaffold_points: Vec<_> = scaffolding.grid.iter().filter(|(_, e)| **e == Elem::Scaffold).map(|(p, _)| p).collect();
         let intersections: Vec<Point> = scaffold_points.iter().filter(|p| {
             let neighbours = vec!(Point { x: p.x - 1, y: p.y }, Point { x: p.x + 1, y: p.y }, Point { x: p.x, y: p.y - 1 }, Point { x: p.x, y: p.y + 1 });
@@ -36,16 +42,16 @@ impl Solver for Problem {
         }).map(|p| **p).collect();
         let alignment_sum = intersections.iter().fold(0, |acc, p: &Point| acc + (p.x * p.y) );
         let _ = scaffolding.handle.join();
-        alignment_sum as u64
+        Ok(alignment_sum as u64)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut scaffolding = Scaffolding::new(&input);
         let _ = scaffolding.populate_grid();
 
         let path = scaffolding.find_path();
         // Compact the string and then find the three patterns TODO, it looks like a dynamic programming problem
-        let compacted_path: String = path.group_by(|a, b| a == b).map(|g| {
+        let compacted_path: String = path.chunk_by(|a, b| a == b).map(|g| {
             if g.len() > 1 {
                 g.len().to_string()
             } else {
@@ -59,12 +65,12 @@ impl Solver for Problem {
         let mut modified_input = input.clone();
         modified_input[0] = 2;
         let mut scaffolding = Scaffolding::new(&modified_input);
-        let _ = scaffolding.print_map();
+        let _ = scaffolding.show_camera();
         let _ = scaffolding.input_routines(&patterns, &movement_routine);
-        let _ = scaffolding.print_map();
-        let _ = scaffolding.rx_chan.recv().unwrap();
-        let output = scaffolding.rx_chan.recv().unwrap();
-        output
+        let _ = scaffolding.show_camera();
+        let _ = scaffolding.rx_chan.recv()?;
+        let output = scaffolding.rx_chan.recv()?;
+        Ok(output)
     }
 }
 
@@ -144,15 +150,15 @@ impl Elem {
         }
     }
 
-    fn to_char(&self, d: &Direction) -> char {
+    fn cell(&self, d: &Direction) -> Cell {
         match self {
-            Elem::Empty => '.',
-            Elem::Scaffold => '#',
+            Elem::Empty => Cell::new('.', Rgb::GREY),
+            Elem::Scaffold => Cell::new('#', Rgb::WHITE),
             Elem::Robot => match d {
-                Direction::Up => '^',
-                Direction::Down => 'v',
-                Direction::Left => '<',
-                Direction::Right => '>',
+                Direction::Up => Cell::new('^', Rgb::RED),
+                Direction::Down => Cell::new('v', Rgb::RED),
+                Direction::Left => Cell::new('<', Rgb::RED),
+                Direction::Right => Cell::new('>', Rgb::RED),
             }
         }
     }
@@ -206,15 +212,21 @@ impl Scaffolding {
         }
     }
 
-    fn print_map(&self) -> Result<(), Box<dyn Error>> {
+    // Reads an image of the camera, which has to be read either way for the robot to carry on
+    fn show_camera(&self) -> Result<(), Box<dyn Error>> {
         const WIDTH: usize = 43 + 1; // for 'newline'
         const HEIGHT: usize = 39;
         let map_size = WIDTH * HEIGHT;
+        let mut image = String::new();
         for _ in 0..map_size {
             let output = self.rx_chan.recv()?;
-            let character = char::from_u32(output as u32).unwrap();
-            print!("{}", character);
+            image.push(char::from_u32(output as u32).unwrap());
         }
+        visualize::frame(|| Frame::from_text(&image, |c| match c {
+            '.' => Elem::Empty.cell(&self.robot_direction),
+            '#' => Elem::Scaffold.cell(&self.robot_direction),
+            c => Cell::new(c, Rgb::RED),
+        }));
         Ok(())
     }
 
@@ -280,24 +292,26 @@ impl Scaffolding {
             while output != 10 {
                 output = self.rx_chan.recv()?;
                 let character = char::from_u32(output as u32).unwrap();
-                print!("{}", character);
+                eprint!("{}", character);
             }
         }
         Ok(())
     }
 
-    fn print(&self) {
-        let min_x = self.grid.keys().min_by_key(|p| p.x).unwrap().x;
-        let min_y = self.grid.keys().min_by_key(|p| p.y).unwrap().y;
-        let max_x = self.grid.keys().max_by_key(|p| p.x).unwrap().x;
-        let max_y = self.grid.keys().max_by_key(|p| p.y).unwrap().y;
+    // Don't flip it since Y is from distance from top
+    fn frame(&self) -> Frame {
+        Frame::from_points(&self.grid, |_, e| e.unwrap_or(&Elem::Empty).cell(&self.robot_direction))
+    }
+}
 
-        // Don't reverse it since Y is from distance from top
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                print!("{}", self.grid.get(&Point{ x: x, y: y }).unwrap().to_char(&self.robot_direction));
-            }
-            println!();
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 17);
     }
 }
@@ -1,12 +1,13 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     grid::Grid,
 };
 use std::{
     collections::{HashMap, HashSet, VecDeque, BinaryHeap, BTreeSet},
     cmp::Ordering,
     convert::TryFrom,
-    fs::File,
+    io::Read,
 };
 use itertools::Itertools;
 
@@ -40,45 +41,48 @@ impl Default for Tile {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 18, title: "Many-Worlds Interpretation", tags: &["grid", "maze", "graph"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Grid<Tile>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        let grid: Grid<Tile> = Grid::from_reader(f).unwrap();
-        grid
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        let grid: Grid<Tile> = Grid::from_reader(f)?;
+        Ok(grid)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let maze = MazeGraph::from_grid(&input);
         let result = maze.search(&['@'].to_vec());
-        assert!(result != None);
-        result.unwrap()
+        Ok(result.ok_or("Not all of the keys can be collected")?)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut grid = input.clone();
         // Close off the centre of the maze into four partitions
-        const ROBOT_LOCATION: (usize, usize) = (40, 40);
-        assert!(*grid.get(ROBOT_LOCATION).unwrap() == Tile::Node('@'));
+        let robot_location = (0..grid.w).cartesian_product(0..grid.h)
+            .find(|p| grid.get(*p) == Some(&Tile::Node('@')))
+            .ok_or("There's no robot in the maze")?;
         let neighbour_vectors: Vec<(i64, i64)> = vec!((-1, 0), (1, 0), (0, -1), (0, 1));
         let robot_neighbours: Vec<_> = neighbour_vectors.iter().map(|(x, y)| {
-            ((x + ROBOT_LOCATION.0 as i64) as usize, (y + ROBOT_LOCATION.1 as i64) as usize)
+            ((x + robot_location.0 as i64) as usize, (y + robot_location.1 as i64) as usize)
         }).collect();
-        grid.set(ROBOT_LOCATION, Tile::Wall);
+        grid.set(robot_location, Tile::Wall);
         (0..4).for_each(|i| grid.set(robot_neighbours[i], Tile::Wall));
         // Add the new robots in
         let new_robot_nodes = vec!(Tile::Node('@'), Tile::Node('!'), Tile::Node('$'), Tile::Node('%'));
         let new_robot_vectors: Vec<(i64, i64)> = vec!((-1, 1), (-1, -1), (1, 1), (1, -1));
         let new_robots: Vec<_> = new_robot_vectors.iter().map(|(x, y)| {
-            ((x + ROBOT_LOCATION.0 as i64) as usize, (y + ROBOT_LOCATION.1 as i64) as usize)
+            ((x + robot_location.0 as i64) as usize, (y + robot_location.1 as i64) as usize)
         }).collect();
         (0..4).for_each(|i| grid.set(new_robots[i], new_robot_nodes[i].clone()));
         let maze = MazeGraph::from_grid(&grid);
         let result = maze.search(&['@', '!', '$', '%'].to_vec());
-        assert!(result != None);
-        result.unwrap()
+        Ok(result.ok_or("Not all of the keys can be collected")?)
     }
 }
 
@@ -124,7 +128,7 @@ pub struct MazeGraph {
 }
 
 impl MazeGraph {
-    fn from_grid(grid: &Grid<Tile>) -> Self {
+    pub fn from_grid(grid: &Grid<Tile>) -> Self {
         let mut graph = HashMap::new();
         for (x, y) in (0..grid.w).cartesian_product(0..grid.h) {
             if let Some(Tile::Node(c)) = grid.get((x, y)) {
@@ -136,8 +140,8 @@ impl MazeGraph {
     }
 
     // Dijkstra's algorithm basically
-    fn search(&self, start: &Vec<char>) -> Option<usize> {
-        const TOTAL_KEY_COUNT: usize = 26;
+    pub fn search(&self, start: &Vec<char>) -> Option<usize> {
+        let total_key_count = self.graph.keys().filter(|k| k.is_lowercase()).count();
         let mut to_evaluate = BinaryHeap::new();
         let mut best_distances: HashMap<(Vec<char>, BTreeSet<char>), usize> = HashMap::new();
         let mut search_cached: HashMap<(char, BTreeSet<char>), Vec<(char, usize)>> = HashMap::new();
@@ -145,7 +149,7 @@ impl MazeGraph {
         to_evaluate.push(start_state);
 
         while let Some(curr_state) = to_evaluate.pop() {
-            if curr_state.keys_held.len() == TOTAL_KEY_COUNT {
+            if curr_state.keys_held.len() == total_key_count {
                 return Some(curr_state.steps_taken);
             }
 
@@ -260,3 +264,84 @@ fn reachable_from(grid: &Grid<Tile>, (from_x, from_y): (usize, usize)) -> HashMa
 
     reachable
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[
+            ("#########\n#b.A.@.a#\n#########", 8),
+            ("\
+########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################", 86),
+            ("\
+########################
+#...............b.C.D.f#
+#.######################
+#.....@.a.B.c.d.A.e.F.g#
+########################", 132),
+            ("\
+#################
+#i.G..c...e..H.p#
+########.########
+#j.A..b...f..D.o#
+########@########
+#k.E..a...g..B.n#
+########.########
+#l.F..d...h..C.m#
+#################", 136),
+            ("\
+########################
+#@..............ac.GI.b#
+###d#e#f################
+###A#B#C################
+###g#h#i################
+########################", 81),
+        ]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[
+            ("#######\n#a.#Cd#\n##...##\n##.@.##\n##...##\n#cB#Ab#\n#######", 8),
+            ("\
+###############
+#d.ABC.#.....a#
+######...######
+######.@.######
+######...######
+#b.....#.....c#
+###############", 24),
+            ("\
+#############
+#DcBa.#.GhKl#
+#.###...#I###
+#e#d#.@.#j#k#
+###C#...###J#
+#fEbA.#.FgHi#
+#############", 32),
+            ("\
+#############
+#g#f.D#..h#l#
+#F###e#E###.#
+#dCba...BcIJ#
+#####.@.#####
+#nK.L...G...#
+#M###N#H###.#
+#o#m..#i#jk.#
+#############", 72),
+        ]);
+    }
+
+    #[test]
+    #[ignore = "slow, needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 18);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
-    solver::Solver,
-    intcode::{parse_program, AsyncIO, IntCodeMachine},
+    registry::Registration,
+    solver::{self, Solver},
+    intcode::parse_program,
+    memo::PureProgram,
 };
 use std::{
-    fs::File,
-    thread,
+    io::Read,
     error::Error,
 };
 use itertools::Itertools;
@@ -27,61 +28,83 @@ impl Tile {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 19, title: "Tractor Beam", tags: &["intcode"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
-        let mut tractor_drone = TractorDrone::new(input);
-        let num_tiles_affected = (0..50).cartesian_product(0..50).fold(0, |acc, (x, y)| {
-            match tractor_drone.check_tile(x, y).unwrap() {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
+        let tractor_drone = TractorDrone::new(input);
+        let tiles: Vec<_> = (0..50).cartesian_product(0..50).collect();
+        let num_tiles_affected = tractor_drone.check_tiles(&tiles)?.iter().fold(0, |acc, t| {
+            match t {
                 Tile::Empty => acc,
                 Tile::Beam => acc + 1,
             }
         });
-        num_tiles_affected
+        Ok(num_tiles_affected)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
-        let mut tractor_drone = TractorDrone::new(input);
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
+        let tractor_drone = TractorDrone::new(input);
         let mut x = 0;
         let mut y = 0;
         // Find the top-right and bottom-left corners of the box
-        while tractor_drone.check_tile(x + 99, y).unwrap() == Tile::Empty {
+        while tractor_drone.check_tile(x + 99, y)? == Tile::Empty {
             y += 1;
-            while tractor_drone.check_tile(x, y + 99).unwrap() == Tile::Empty {
+            while tractor_drone.check_tile(x, y + 99)? == Tile::Empty {
                 x += 1;
             }
         }
-        x * 10000 + y
+        Ok(x * 10000 + y)
     }
 }
 
 pub struct TractorDrone {
-    program: Vec<i64>
+    program: PureProgram,
 }
 
 impl TractorDrone {
     fn new(program: &Vec<i64>) -> Self {
         Self {
-            program: program.clone(),
+            program: PureProgram::from_env(program, "day19"),
         }
     }
 
-    fn check_tile(&mut self, x: usize, y: usize) -> Result<Tile, Box<dyn Error>> {
-        let (io, tx, rx) = AsyncIO::new();
-        let mut machine = IntCodeMachine::new(&self.program, io);
-        let handle = thread::spawn(move || machine.run());
+    fn check_tile(&self, x: usize, y: usize) -> Result<Tile, Box<dyn Error>> {
         // Send x and y as input coordinates
-        tx.send(x as i64)?;
-        tx.send(y as i64)?;
-        let result = rx.recv()?;
-        let _ =handle.join();
-        Ok(Tile::from_i64(result))
+        let output = self.program.call(&[x as i64, y as i64])?;
+        let result = output.first().ok_or("Drone didn't report the tile!")?;
+        Ok(Tile::from_i64(*result))
+    }
+
+    // Probes all the tiles at once across the pool
+    fn check_tiles(&self, tiles: &[(usize, usize)]) -> Result<Vec<Tile>, Box<dyn Error>> {
+        let inputs: Vec<_> = tiles.iter().map(|(x, y)| vec!(*x as i64, *y as i64)).collect();
+        self.program.call_batch(&inputs).into_iter().map(|output| {
+            let output = output?;
+            let result = output.first().ok_or("Drone didn't report the tile!")?;
+            Ok(Tile::from_i64(*result))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 19);
     }
 }
@@ -1,12 +1,12 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     grid::Grid,
 };
 use std::{
     collections::{HashMap, HashSet, VecDeque, BinaryHeap, BTreeSet},
     cmp::Ordering,
-    io::{BufRead, BufReader},
-    fs::File,
+    io::{BufRead, BufReader, Read},
 };
 use itertools::Itertools;
 
@@ -40,21 +40,22 @@ impl Tile {
 
 pub const MAZE_OFFSET_X: usize = 2;
 pub const MAZE_OFFSET_Y: usize = 2;
-pub const MAZE_WIDTH: usize = 113;
-pub const MAZE_HEIGHT: usize = 111;
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 20, title: "Donut Maze", tags: &["grid", "maze"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = PortalGraph;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, f: File) -> Self::Input {
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
         let lines_vec: Vec<String> = BufReader::new(f)
             .lines()
-            .filter_map(|l| l.ok())
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         // Insert characters from the file into a HashMap
         let mut char_grid: HashMap<(usize, usize), char> = HashMap::new();
@@ -64,25 +65,39 @@ impl Solver for Problem {
             });
         });
 
-        // Parse the actual maze
+        // The outer wall is on the first row and column past the portal labels
+        let maze_width = lines_vec.get(MAZE_OFFSET_Y).map_or(0, |l| l.trim_end().len()).saturating_sub(MAZE_OFFSET_X);
+        let maze_height = lines_vec.iter().skip(MAZE_OFFSET_Y).take_while(|l| {
+            matches!(l.chars().nth(MAZE_OFFSET_X), Some('#') | Some('.'))
+        }).count();
+
+        // Parse the actual maze, trailing spaces may have been trimmed from the lines
         let mut grid: HashMap<(usize, usize), Tile> = HashMap::new();
-        (0..MAZE_WIDTH).cartesian_product(0..MAZE_HEIGHT).for_each(|(x, y)| {
-            grid.insert((x, y), Tile::from_char(char_grid.get(&(x + MAZE_OFFSET_X, y + MAZE_OFFSET_Y)).unwrap()));
+        (0..maze_width).cartesian_product(0..maze_height).for_each(|(x, y)| {
+            grid.insert((x, y), Tile::from_char(char_grid.get(&(x + MAZE_OFFSET_X, y + MAZE_OFFSET_Y)).unwrap_or(&' ')));
         });
 
+        // Find the hole in the middle of the maze by looking across it
+        let hole_xs: Vec<_> = (0..maze_width).filter(|x| grid[&(*x, maze_height / 2)] == Tile::Void).collect();
+        let hole_ys: Vec<_> = (0..maze_height).filter(|y| grid[&(maze_width / 2, *y)] == Tile::Void).collect();
+        let inner_bounds = match (hole_xs.first(), hole_xs.last(), hole_ys.first(), hole_ys.last()) {
+            (Some(l), Some(r), Some(t), Some(b)) if *l > 0 && *t > 0 => (l - 1, r + 1, t - 1, b + 1),
+            _ => return Err("The maze has no hole in the middle".into()),
+        };
+
         // Populate the portals now
-        populate_portals(&mut grid, &char_grid, (0, MAZE_WIDTH - 1, 0, MAZE_HEIGHT - 1), true);
-        populate_portals(&mut grid, &char_grid, (28, 84, 28, 82), false);
+        populate_portals(&mut grid, &char_grid, (0, maze_width - 1, 0, maze_height - 1), true);
+        populate_portals(&mut grid, &char_grid, inner_bounds, false);
 
-        PortalGraph::from_grid(&grid)
+        Ok(PortalGraph::from_grid(&grid))
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
-        input.shortest_distance("AA0", "ZZ0")
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
+        Ok(input.shortest_distance("AA0", "ZZ0"))
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
-        input.find_shortest_recursive_distance().unwrap()
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
+        Ok(input.find_shortest_recursive_distance().ok_or("There's no path out of the recursive maze")?)
     }
 }
 
@@ -199,9 +214,9 @@ pub struct PortalGraph {
 impl PortalGraph {
     fn from_grid(grid: &HashMap<(usize, usize), Tile>) -> Self {
         let mut graph = HashMap::new();
-        for (x, y) in (0..MAZE_WIDTH).cartesian_product(0..MAZE_HEIGHT) {
-            if let Some(Tile::Portal(s)) = grid.get(&(x, y)) {
-                let reachable_nodes = reachable_from(grid, (x, y));
+        for (p, tile) in grid.iter() {
+            if let Tile::Portal(s) = tile {
+                let reachable_nodes = reachable_from(grid, *p);
                 graph.insert(String::from(s), reachable_nodes);
             }
         }
@@ -298,7 +313,7 @@ fn reachable_from(grid: &HashMap<(usize, usize), Tile>, (from_x, from_y): (usize
     to_visit.push_back((from_x, from_y, 1));
 
     while let Some((curr_x, curr_y, steps)) = to_visit.pop_front() {
-        let neighbours = [(curr_x - 1, curr_y), (curr_x + 1, curr_y), (curr_x, curr_y - 1), (curr_x, curr_y + 1)];
+        let neighbours = [(curr_x.wrapping_sub(1), curr_y), (curr_x + 1, curr_y), (curr_x, curr_y.wrapping_sub(1)), (curr_x, curr_y + 1)];
         for n in neighbours {
             if let Some(tile) = grid.get(&n) {
                 if !visited.contains(&n) {
@@ -315,3 +330,45 @@ fn reachable_from(grid: &HashMap<(usize, usize), Tile>, (from_x, from_y): (usize
 
     reachable
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input, check_second};
+
+    const EXAMPLE: &str = r"         A           
+         A           
+  #######.#########  
+  #######.........#  
+  #######.#######.#  
+  #######.#######.#  
+  #######.#######.#  
+  #####  B    ###.#  
+BC...##  C    ###.#  
+  ##.##       ###.#  
+  ##...DE  F  ###.#  
+  #####    G  ###.#  
+  #########.#####.#  
+DE..#######...###.#  
+  #.#########.###.#  
+FG..#########.....#  
+  ###########.#####  
+             Z       
+             Z       ";
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[(EXAMPLE, 23)]);
+    }
+
+    #[test]
+    fn second_examples() {
+        check_second(&Problem, &[(EXAMPLE, 26)]);
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 20);
+    }
+}
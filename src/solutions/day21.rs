@@ -1,9 +1,10 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     intcode::{parse_program, AsyncIO, IntCodeMachine}
 };
 use std::{
-    fs::File,
+    io::Read,
     thread,
     error::Error,
     sync::mpsc::{Sender, Receiver}
@@ -11,16 +12,20 @@ use std::{
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 21, title: "Springdroid Adventure", tags: &["intcode"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = i64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut spring_droid = SpringDroid::new(input);
         // Basically, jump if there's a hole in front of us or there's a hole
         // three tiles away that we need to jump early for and there's ground
@@ -29,31 +34,23 @@ impl Solver for Problem {
         let _ = spring_droid.input_script(script);
         let output = spring_droid.run_script();
         let _ = spring_droid.handle.join();
-        match output {
-            Ok(o) => match o {
-                0 => println!("Springdroid fell through the hull and into space..."),
-                _ => return o,
-            }
-            _ => println!("Springdroid failed somewhere"),
+        match output? {
+            0 => Err("Springdroid fell through the hull and into space...".into()),
+            o => Ok(o),
         }
-        0
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let mut spring_droid = SpringDroid::new(input);
         // Basically jump if there's space 4 and 8 ahead
         let script = "NOT C J\nAND D J\nAND H J\nNOT B T\nAND D T\nOR T J\nNOT A T\nOR T J\nRUN\n";
         let _ = spring_droid.input_script(script);
         let output = spring_droid.run_script();
         let _ = spring_droid.handle.join();
-        match output {
-            Ok(o) => match o {
-                0 => println!("Springdroid fell through the hull and into space..."),
-                _ => return o,
-            }
-            _ => println!("Springdroid failed somewhere"),
+        match output? {
+            0 => Err("Springdroid fell through the hull and into space...".into()),
+            o => Ok(o),
         }
-        0
     }
 }
 
@@ -76,15 +73,15 @@ impl SpringDroid {
     }
 
     fn input_script(&mut self, script: &str) -> Result<(), Box<dyn Error>> {
-        // Print out the prompt
+        // Print out the prompt, on stderr so that stdout only has the answers
         for _ in 0..20 {
             let output = self.rx_chan.recv()?;
             let character = char::from_u32(output as u32).unwrap();
-            print!("{}", character);
+            eprint!("{}", character);
         }
         // Input the script
         for c in script.chars() {
-            print!("{}", c);
+            eprint!("{}", c);
             self.tx_chan.send(c as i64)?;
         }
         Ok(())
@@ -97,8 +94,20 @@ impl SpringDroid {
                 return Ok(c);
             }
             let character = char::from_u32(c as u32).unwrap();
-            print!("{}", character);
+            eprint!("{}", character);
         }
         Ok(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 21);
+    }
+}
@@ -1,36 +1,37 @@
-use crate::solver::Solver;
+use crate::parse::{self, ParseError, Scanner};
+use crate::registry::Registration;
+use crate::solver::{self, Solver};
 use std::{
-    io::{BufRead, BufReader},
-    fs::File,
+    io::Read,
 };
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 22, title: "Slam Shuffle", tags: &["math"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<Technique>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        BufReader::new(f)
-            .lines()
-            .filter_map(|l| l.ok())
-            .map(|l| Technique::new(&l))
-            .collect()
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        parse::lines(f, Technique::parse)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut shuffler = Shuffler::new(10007);
         shuffler.shuffle(&input);
-        shuffler.cards.iter().enumerate().find(|(_, x)| **x == 2019).unwrap().0
+        Ok(shuffler.cards.iter().position(|x| *x == 2019).ok_or("Card 2019 went missing")?)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
         let shuffler = Shuffler::new(0);
         let size = 119315717514047;
         let iterations = 101741582076661;
         let pos = 2020;
-        shuffler.shuffle_long(&input, size, iterations, pos)
+        Ok(shuffler.shuffle_long(&input, size, iterations, pos))
     }
 }
 
@@ -48,18 +49,17 @@ pub struct Technique {
 }
 
 impl Technique {
-    fn new(desc: &String) -> Self {
-        if desc.contains("cut") {
-            let argument = desc.split(" ").last().unwrap().parse().unwrap();
-            Self { technique_type: TechniqueType::Cut, argument: argument }
-        } else if desc.contains("deal with") {
-            let argument = desc.split(" ").last().unwrap().parse().unwrap();
-            Self { technique_type: TechniqueType::DealWith, argument: argument }
-        } else if desc.contains("deal into") {
-            Self { technique_type: TechniqueType::DealStack, argument: 0 }
-        } else {
-            panic!("Invalid technique string!");
-        }
+    fn parse(s: &mut Scanner) -> Result<Self, ParseError> {
+        let technique_type = s.one_of(&[
+            ("cut ", TechniqueType::Cut),
+            ("deal with increment ", TechniqueType::DealWith),
+            ("deal into new stack", TechniqueType::DealStack),
+        ])?;
+        let argument = match technique_type {
+            TechniqueType::DealStack => 0,
+            _ => s.integer()?,
+        };
+        Ok(Self { technique_type, argument })
     }
 }
 
@@ -193,3 +193,47 @@ fn polypow(a: i128, b: i128, m: i128, n: i128) -> (i128, i128) {
         ((a * c).rem_euclid(n), (a * d + b).rem_euclid(n))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    const EXAMPLES: [(&str, [usize; 10]); 4] = [
+        ("deal with increment 7\ndeal into new stack\ndeal into new stack", [0, 3, 6, 9, 2, 5, 8, 1, 4, 7]),
+        ("cut 6\ndeal with increment 7\ndeal into new stack", [3, 0, 7, 4, 1, 8, 5, 2, 9, 6]),
+        ("deal with increment 7\ndeal with increment 9\ncut -2", [6, 3, 0, 7, 4, 1, 8, 5, 2, 9]),
+        ("deal into new stack\ncut -2\ndeal with increment 7\ncut 8\ncut -4\ndeal with increment 7\ncut 3\ndeal with increment 9\ndeal with increment 3\ncut -1", [9, 2, 5, 8, 1, 4, 7, 0, 3, 6]),
+    ];
+
+    // The examples shuffle a deck of 10 cards
+    #[test]
+    fn shuffle_examples() {
+        for (input, deck) in EXAMPLES.iter() {
+            let mut shuffler = Shuffler::new(10);
+            shuffler.shuffle(&Problem.parse_str(input).unwrap());
+            assert_eq!(shuffler.cards, deck.to_vec(), "{}", input);
+        }
+    }
+
+    // The long shuffle needs a prime deck size, so compare it with shuffling 11 cards a few times
+    #[test]
+    fn shuffle_long_matches_shuffle() {
+        for (input, _) in EXAMPLES.iter() {
+            let techniques = Problem.parse_str(input).unwrap();
+            let mut shuffler = Shuffler::new(11);
+            for iterations in 1..=3 {
+                shuffler.shuffle(&techniques);
+                for pos in 0..11 {
+                    assert_eq!(shuffler.shuffle_long(&techniques, 11, iterations, pos), shuffler.cards[pos], "{}", input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 22);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     intcode::{parse_program, IntCodeMachine, IO}
 };
 use std::{
-    fs::File,
+    io::Read,
     thread,
     error::Error,
     sync::mpsc::{channel, Sender, Receiver, TryRecvError},
@@ -18,26 +19,31 @@ const NUM_ROUTERS: usize = 50;
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 23, title: "Category Six", tags: &["intcode"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = i64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    // Part 2 decides the network is idle by waiting, the other part's 50 machines make that worse
+    const PARALLEL_PARTS: bool = false;
+
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut network = Network::new(input, NUM_ROUTERS);
-        let result = network.run_network(true);
-        result.unwrap()
+        network.run_network(true)
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
-        println!("This will take some time...");
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
+        eprintln!("This will take some time...");
         let mut network = Network::new(input, NUM_ROUTERS);
-        let result = network.run_network(false);
-        result.unwrap()
+        network.run_network(false)
     }
 }
 
@@ -182,3 +188,16 @@ impl Network {
         Ok(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input_part;
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        // Part 2 relies on timing the routers, so it has no fixed answer to check
+        check_full_input_part(&Problem, 23, 1);
+    }
+}
@@ -1,10 +1,10 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
 };
 use std::{
     collections::{HashMap, HashSet},
-    io::{BufRead, BufReader},
-    fs::File,
+    io::{BufRead, BufReader, Read},
 };
 use itertools::Itertools;
 
@@ -19,11 +19,11 @@ const GRID_HEIGHT: usize = 5;
 const ITERATIONS: usize = 200;
 
 impl Tile {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Result<Self, String> {
         match c {
-            '.' => Tile::Empty,
-            '#' => Tile::Bug,
-            v => panic!("Invalid character: {}", v)
+            '.' => Ok(Tile::Empty),
+            '#' => Ok(Tile::Bug),
+            v => Err(format!("Invalid character: {}", v)),
         }
     }
 
@@ -37,16 +37,20 @@ impl Tile {
 
 pub struct Problem;
 
+inventory::submit! {
+    Registration { year: 2019, day: 24, title: "Planet of Discord", tags: &["grid"], solver: &Problem }
+}
+
 impl Solver for Problem {
     type Input = BugGrid;
     type Output1 = u64;
     type Output2 = usize;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        BugGrid::from_file(f)
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        BugGrid::from_reader(f)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut bug_grid = (*input).clone();
         // We can effectively reduce the grid into a number
         let mut seen_grids: HashSet<u64> = HashSet::new();
@@ -55,26 +59,30 @@ impl Solver for Problem {
             bug_grid.advance();
             let rating = bug_grid.biodiversity_rating();
             if seen_grids.contains(&rating) {
-                return rating;
+                return Ok(rating);
             }
             seen_grids.insert(rating);
         }
     }
 
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2 {
-        let mut recursive_grid = RecursiveBugGrid::new(ITERATIONS);
-        // Initialise the starting grid
-        (0..GRID_HEIGHT).cartesian_product(0..GRID_WIDTH).for_each(|(y, x)| {
-            let tile = input.grid.get(&(x as i64, y as i64)).unwrap();
-            recursive_grid.grids.get_mut(&(ITERATIONS + 1)).unwrap().insert((x as i64, y as i64), tile.clone());
-        });
-        for _ in 0..ITERATIONS {
-            recursive_grid.advance();
-        }
-        recursive_grid.grids.values().fold(0, |acc, g| {
-            acc + g.values().filter(|t| **t == Tile::Bug).count()
-        })
+    fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
+        count_recursive_bugs(input, ITERATIONS)
+    }
+}
+
+fn count_recursive_bugs(input: &BugGrid, iterations: usize) -> solver::Result<usize> {
+    let mut recursive_grid = RecursiveBugGrid::new(iterations);
+    // Initialise the starting grid
+    for (y, x) in (0..GRID_HEIGHT).cartesian_product(0..GRID_WIDTH) {
+        let tile = input.grid.get(&(x as i64, y as i64)).ok_or("The grid should be 5x5")?;
+        recursive_grid.grids.get_mut(&(iterations + 1)).unwrap().insert((x as i64, y as i64), tile.clone());
+    }
+    for _ in 0..iterations {
+        recursive_grid.advance();
     }
+    Ok(recursive_grid.grids.values().fold(0, |acc, g| {
+        acc + g.values().filter(|t| **t == Tile::Bug).count()
+    }))
 }
 
 struct RecursiveBugGrid {
@@ -249,18 +257,14 @@ pub struct BugGrid {
 }
 
 impl BugGrid {
-    fn from_file(f: File) -> Self {
+    fn from_reader<R: Read>(f: R) -> solver::Result<Self> {
         let mut output: HashMap<(i64, i64), Tile> = HashMap::new();
-        BufReader::new(f)
-            .lines()
-            .filter_map(|l| l.ok())
-            .enumerate()
-            .for_each(|(y, l)| {
-                l.char_indices().for_each(|(x, c)| {
-                    output.insert((x as i64, y as i64), Tile::from_char(c));
-                });
-            });
-        Self { grid: output }
+        for (y, l) in BufReader::new(f).lines().enumerate() {
+            for (x, c) in l?.char_indices() {
+                output.insert((x as i64, y as i64), Tile::from_char(c)?);
+            }
+        }
+        Ok(Self { grid: output })
     }
 
     fn biodiversity_rating(&self) -> u64 {
@@ -305,3 +309,28 @@ impl BugGrid {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_first, check_full_input};
+
+    const EXAMPLE: &str = "....#\n#..#.\n#..##\n..#..\n#....";
+
+    #[test]
+    fn first_examples() {
+        check_first(&Problem, &[(EXAMPLE, 2129920)]);
+    }
+
+    // The example only runs for 10 minutes
+    #[test]
+    fn recursive_example() {
+        assert_eq!(count_recursive_bugs(&Problem.parse_str(EXAMPLE).unwrap(), 10).unwrap(), 99);
+    }
+
+    #[test]
+    #[ignore = "slow, needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 24);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
-    solver::Solver,
+    registry::Registration,
+    solver::{self, Solver},
     intcode::{parse_program, AsyncIO, IntCodeMachine}
 };
 use std::{
-    fs::File,
+    io::Read,
     thread,
     error::Error,
     sync::mpsc::{Sender, Receiver, TryRecvError},
@@ -12,56 +13,68 @@ use std::{
 
 pub struct Problem;
 
-const INTERACTIVE: bool = false;
+inventory::submit! {
+    Registration { year: 2019, day: 25, title: "Cryostasis", tags: &["intcode"], solver: &Problem }
+}
 
 impl Solver for Problem {
     type Input = Vec<i64>;
     type Output1 = i64;
     type Output2 = i64;
 
-    fn parse_input(&self, f: File) -> Self::Input {
-        parse_program(f)
+    const INTERACTIVE: bool = true;
+    const SECOND_PART: bool = false;
+
+    fn parse_input<R: Read>(&self, f: R) -> solver::Result<Self::Input> {
+        Ok(parse_program(f)?)
     }
 
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1 {
+    fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
         let mut navigator_droid = NavigatorDroid::new(input);
-        if INTERACTIVE {
-            loop {
-                navigator_droid.print_output().expect("Failed to print out output from the navigator droid!");
-                navigator_droid.prompt_command().expect("Failed to input command for the navigator droid!");
-            }
-        } else {
-            // Found the solution interactively
-            let mut instructions = vec!();
-            instructions.push("south\n");
-            instructions.push("west\n");
-            instructions.push("south\n");
-            instructions.push("take shell\n");
-            instructions.push("north\n");
-            instructions.push("north\n");
-            instructions.push("take weather machine\n");
-            instructions.push("west\n");
-            instructions.push("south\n");
-            instructions.push("east\n");
-            instructions.push("take candy cane\n");
-            instructions.push("west\n");
-            instructions.push("north\n");
-            instructions.push("east\n");
-            instructions.push("south\n");
-            instructions.push("east\n");
-            instructions.push("east\n");
-            instructions.push("south\n");
-            instructions.push("take hypercube\n");
-            instructions.push("south\n");
-            instructions.push("south\n");
-            instructions.push("east\n");
-            let _ = navigator_droid.input_script(&instructions);
-        }
-        0
+        // Found the solution interactively
+        let mut instructions = vec!();
+        instructions.push("south\n");
+        instructions.push("west\n");
+        instructions.push("south\n");
+        instructions.push("take shell\n");
+        instructions.push("north\n");
+        instructions.push("north\n");
+        instructions.push("take weather machine\n");
+        instructions.push("west\n");
+        instructions.push("south\n");
+        instructions.push("east\n");
+        instructions.push("take candy cane\n");
+        instructions.push("west\n");
+        instructions.push("north\n");
+        instructions.push("east\n");
+        instructions.push("south\n");
+        instructions.push("east\n");
+        instructions.push("east\n");
+        instructions.push("south\n");
+        instructions.push("take hypercube\n");
+        instructions.push("south\n");
+        instructions.push("south\n");
+        instructions.push("east\n");
+        let output = navigator_droid.input_script(&instructions)?;
+        // Santa gives the password for the airlock once the droid is past the security checkpoint
+        let password = output.split("typing ").nth(1).and_then(|s| s.split_whitespace().next())
+            .ok_or("The droid didn't make it past the security checkpoint")?;
+        Ok(password.parse()?)
+    }
+
+    fn solve_second(&self, _input: &Self::Input) -> solver::Result<Self::Output2> {
+        Err("There is no second puzzle on the last day".into())
     }
 
-    fn solve_second(&self, _input: &Self::Input) -> Self::Output2 {
-        0
+    // The droid's program halts once it gets past the security checkpoint
+    fn play(&self, input: &Self::Input) -> solver::Result<()> {
+        let mut navigator_droid = NavigatorDroid::new(input);
+        while navigator_droid.print_output().is_ok() {
+            if !navigator_droid.prompt_command()? {
+                break;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -100,17 +113,21 @@ impl NavigatorDroid {
         Ok(())
     }
 
-    fn prompt_command(&mut self) -> Result<(), Box<dyn Error>> {
+    // Returns false once there are no more commands
+    fn prompt_command(&mut self) -> Result<bool, Box<dyn Error>> {
         let mut command = String::new();
-        stdin().read_line(&mut command).expect("Failed to read in command for navigator!");
+        if stdin().read_line(&mut command)? == 0 {
+            return Ok(false);
+        }
         for c in command.trim().chars() {
             self.tx_chan.send(c as i64)?;
         }
         self.tx_chan.send(10)?;
-        Ok(())
+        Ok(true)
     }
 
-    fn input_script(&mut self, instructions: &Vec<&str>) -> Result<(), Box<dyn Error>> {
+    // Everything the droid reports until its program halts
+    fn input_script(&mut self, instructions: &Vec<&str>) -> Result<String, Box<dyn Error>> {
         for i in instructions {
             for c in i.chars() {
                 self.tx_chan.send(c as i64)?;
@@ -118,10 +135,22 @@ impl NavigatorDroid {
         }
 
         // Drain the output now
-        loop {
-            let c = self.rx_chan.recv()?;
-            let character = char::from_u32(c as u32).unwrap();
-            print!("{}", character);
+        let mut output = String::new();
+        while let Ok(c) = self.rx_chan.recv() {
+            output.push(char::from_u32(c as u32).ok_or("The droid output an invalid character")?);
         }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::check_full_input;
+
+    #[test]
+    #[ignore = "needs the full input"]
+    fn full_input() {
+        check_full_input(&Problem, 25);
     }
 }
@@ -1,38 +1,376 @@
+//! What a solution implements, and running one against its input.
+//!
+//! ```
+//! use aoc_2019::solver::{self, Solver};
+//! use std::io::{BufRead, BufReader, Read};
+//!
+//! struct Sums;
+//!
+//! impl Solver for Sums {
+//!     type Input = Vec<i64>;
+//!     type Output1 = i64;
+//!     type Output2 = i64;
+//!
+//!     fn parse_input<R: Read>(&self, r: R) -> solver::Result<Self::Input> {
+//!         let mut numbers = vec!();
+//!         for line in BufReader::new(r).lines() {
+//!             numbers.push(line?.parse()?);
+//!         }
+//!         Ok(numbers)
+//!     }
+//!
+//!     fn solve_first(&self, input: &Self::Input) -> solver::Result<Self::Output1> {
+//!         Ok(input.iter().sum())
+//!     }
+//!
+//!     fn solve_second(&self, input: &Self::Input) -> solver::Result<Self::Output2> {
+//!         input.iter().max().copied().ok_or_else(|| "No numbers".into())
+//!     }
+//! }
+//!
+//! assert_eq!(Sums.solve_first_str("1\n2\n3").unwrap(), 6);
+//! assert_eq!(Sums.solve_second_str("1\n2\n3").unwrap(), 3);
+//! ```
+//!
+//! [`Solver::solve`] runs both parts on the input of a day as given by [`Options`].
+
+use crate::{heap::{self, Usage}, timing::time_stage, visualize::{self, Target}};
 use std::{
-    fmt::Display,
+    any::Any,
+    env,
+    error::Error,
+    fmt::{self, Display},
     fs::File,
-    io::self,
+    io::{self, Read},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    thread,
+    time::Duration,
 };
 
-fn input_file(day: i32) -> String {
-    format!("input/day{:02}.txt", day)
+/// What each stage of a solution returns, the day and stage are filled in by [`Solver::solve`].
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// The event these solutions were written for, its inputs are kept directly in the input root.
+pub const DEFAULT_YEAR: i32 = 2019;
+
+/// The environment variable that overrides where the inputs are, see [`input_root`].
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+const DEFAULT_INPUT_DIR: &str = "input";
+
+/// Where the inputs live: AOC_INPUT_DIR if it is set, otherwise input/ of the
+/// current directory or failing that the one in this repository, so the binary
+/// can be run from anywhere.
+pub fn input_root() -> String {
+    if let Ok(dir) = env::var(INPUT_DIR_VAR) {
+        return dir;
+    }
+    if Path::new(DEFAULT_INPUT_DIR).is_dir() {
+        return String::from(DEFAULT_INPUT_DIR);
+    }
+    format!("{}/{}", env!("CARGO_MANIFEST_DIR"), DEFAULT_INPUT_DIR)
+}
+
+/// Something kept next to the input root `root`, e.g. the expected answers or
+/// `target/`, so that it is found from anywhere the inputs are.
+///
+/// ```
+/// use aoc_2019::solver::beside_root;
+///
+/// assert_eq!(beside_root("input", "answers.txt"), "answers.txt");
+/// assert_eq!(beside_root("/src/aoc_2019/input", "target/bench"), "/src/aoc_2019/target/bench");
+/// ```
+pub fn beside_root(root: &str, name: &str) -> String {
+    match Path::new(root).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => format!("{}/{}", dir.display(), name),
+        _ => name.to_string(),
+    }
+}
+
+/// A named set of inputs, e.g. a teammate's, is laid out like the root inside `<root>/<name>/`.
+pub fn input_set_dir(root: &str, set: &str) -> String {
+    format!("{}/{}", root, set)
+}
+
+/// The input of a day in `dir`, `dayNN.txt` for [`DEFAULT_YEAR`] and `YYYY/dayNN.txt` otherwise.
+pub fn input_file_in(dir: &str, year: i32, day: i32) -> String {
+    if year == DEFAULT_YEAR {
+        format!("{}/day{:02}.txt", dir, day)
+    } else {
+        format!("{}/{}/day{:02}.txt", dir, year, day)
+    }
+}
+
+/// The input of a day in the [`input_root`].
+pub fn input_file(year: i32, day: i32) -> String {
+    input_file_in(&input_root(), year, day)
+}
+
+/// Where a day reads its input from.
+#[derive(Clone)]
+pub enum InputSource {
+    /// The day's file in a directory, see [`input_file_in`].
+    Dir(String),
+    /// This file, whichever day it is.
+    Path(String),
+    /// Standard input.
+    Stdin,
+}
+
+impl InputSource {
+    /// The file to read for a day, none for stdin.
+    pub fn path(&self, year: i32, day: i32) -> Option<String> {
+        match self {
+            InputSource::Dir(dir) => Some(input_file_in(dir, year, day)),
+            InputSource::Path(p) => Some(p.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+}
+
+/// How to run a day.
+#[derive(Clone)]
+pub struct Options {
+    /// The event the day is from.
+    pub year: i32,
+    /// Only run this part, both if unset.
+    pub part: Option<u8>,
+    /// Where to read the input from.
+    pub input: InputSource,
+    /// Only print the answers.
+    pub quiet: bool,
+    /// Show what is still running on stderr.
+    pub progress: bool,
+    /// Run both parts at the same time where the day allows it.
+    pub parallel: bool,
+    /// Measure the heap usage of each stage, the days have to run one at a time
+    /// for it and [`heap::CountingAllocator`] has to be the global allocator.
+    pub memory: bool,
+    /// Where to send the frames the days draw while solving, the days have to
+    /// run one at a time for it.
+    pub visualize: Option<Target>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { year: DEFAULT_YEAR, part: None, input: InputSource::Dir(input_root()), quiet: false, progress: false, parallel: false, memory: false, visualize: None }
+    }
 }
 
-pub trait Solver {
-    type Input;
+/// The answer to one part.
+pub struct Answer {
+    /// The answer as it is given on the website.
+    pub value: String,
+    /// How long solving the part took.
+    pub time: Duration,
+    /// The heap usage of solving the part, when measured.
+    pub memory: Option<Usage>,
+}
+
+/// The answers of a day, each part is only there if it was run.
+pub struct DayResult {
+    /// How long reading and parsing the input took.
+    pub parse_time: Duration,
+    /// The heap usage of reading and parsing the input, when measured.
+    pub parse_memory: Option<Usage>,
+    /// The answer to part 1.
+    pub first: Option<Answer>,
+    /// The answer to part 2.
+    pub second: Option<Answer>,
+}
+
+impl DayResult {
+    /// How long the whole day took.
+    pub fn total_time(&self) -> Duration {
+        self.parse_time
+            + self.first.as_ref().map_or(Duration::default(), |a| a.time)
+            + self.second.as_ref().map_or(Duration::default(), |a| a.time)
+    }
+
+    /// Prints each answer, on its own without a label when `quiet`.
+    pub fn print(&self, quiet: bool) {
+        for (i, answer) in [&self.first, &self.second].iter().enumerate() {
+            match answer {
+                Some(a) if quiet => println!("{}", a.value),
+                // Images and the like read better starting on their own line
+                Some(a) if a.value.contains('\n') => println!("Solution {}:\n{}", i + 1, a.value),
+                Some(a) => println!("Solution {}: {}", i + 1, a.value),
+                None => (),
+            }
+        }
+    }
+}
+
+/// How far a day got before it failed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+    /// Finding the solution of the day.
+    Lookup,
+    /// Opening the input.
+    Load,
+    /// Parsing the input.
+    Parse,
+    /// Solving part 1.
+    Part1,
+    /// Solving part 2.
+    Part2,
+}
+
+/// Why a day failed, an error or a panic in one of its stages.
+#[derive(Debug)]
+pub struct SolveError {
+    /// The day that failed.
+    pub day: i32,
+    /// The stage it failed in.
+    pub stage: Stage,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl SolveError {
+    /// An error of a day's stage.
+    pub fn new(day: i32, stage: Stage, message: impl Display) -> Self {
+        Self { day, stage, message: message.to_string() }
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.stage {
+            Stage::Lookup => write!(f, "Day {} {}", self.day, self.message),
+            Stage::Load => write!(f, "Day {} failed to load its input: {}", self.day, self.message),
+            Stage::Parse => write!(f, "Day {} failed to parse its input: {}", self.day, self.message),
+            Stage::Part1 => write!(f, "Day {} part 1 failed: {}", self.day, self.message),
+            Stage::Part2 => write!(f, "Day {} part 2 failed: {}", self.day, self.message),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => format!("panicked: {}", s),
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => format!("panicked: {}", s),
+            Err(_) => String::from("panicked"),
+        },
+    }
+}
+
+// Runs one stage, turning both errors and panics into a SolveError so the other days can still run
+fn run_stage<T>(day: i32, stage: Stage, f: impl FnOnce() -> Result<T>) -> std::result::Result<T, SolveError> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(t)) => Ok(t),
+        Ok(Err(e)) => Err(SolveError::new(day, stage, e)),
+        Err(payload) => Err(SolveError::new(day, stage, panic_message(payload))),
+    }
+}
+
+/// The solution of a day, parsing its input once for both parts.
+///
+/// Both parts borrow the parsed input, so it has to be Sync for them to run at the same time.
+pub trait Solver: Sync {
+    /// The parsed input.
+    type Input: Sync;
+    /// The answer to part 1.
     type Output1: Display;
+    /// The answer to part 2.
     type Output2: Display;
 
-    //fn parse_input<R: io::Seek + io::Read>(&self, r: R) -> Self::Input;
-    fn parse_input(&self, f: File) -> Self::Input;
-    fn solve_first(&self, input: &Self::Input) -> Self::Output1;
-    fn solve_second(&self, input: &Self::Input) -> Self::Output2;
+    /// Parses the puzzle input.
+    fn parse_input<R: Read>(&self, r: R) -> Result<Self::Input>;
+    /// Solves part 1.
+    fn solve_first(&self, input: &Self::Input) -> Result<Self::Output1>;
+    /// Solves part 2.
+    fn solve_second(&self, input: &Self::Input) -> Result<Self::Output2>;
+
+    /// Days whose parts get in each other's way, e.g. by relying on timing, can opt out
+    /// of running them at the same time.
+    const PARALLEL_PARTS: bool = true;
+
+    /// Days that can also be played by hand, see [`play`](Self::play).
+    const INTERACTIVE: bool = false;
+
+    /// The last day only has the one puzzle, days without a second part never
+    /// have [`solve_second`](Self::solve_second) run.
+    const SECOND_PART: bool = true;
 
-    //fn load_input<P: AsRef<Path>>(&self, p: P) -> io::Result<Self::Input> {
-    fn load_input(&self, file_path: String) -> io::Result<Self::Input> {
-        let f = File::open(file_path)?;
-        Ok(self.parse_input(f))
+    /// A live session of the day on the terminal, for days that are
+    /// [`INTERACTIVE`](Self::INTERACTIVE).
+    fn play(&self, _input: &Self::Input) -> Result<()> {
+        Err("This day can't be played by hand".into())
     }
 
-    fn solve(&self, day: i32) {
-        let input_file = input_file(day);
-        let input = self
-            .load_input(input_file)
-            .expect(&format!("Unable to open input file input/day{:02}.txt!", day));
-        let s1 = self.solve_first(&input);
-        let s2 = self.solve_second(&input);
-        println!("Solution 1: {}", s1);
-        println!("Solution 2: {}", s2);
+    /// Parses an input given as a string, e.g. an example from the puzzle description.
+    fn parse_str(&self, s: &str) -> Result<Self::Input> {
+        self.parse_input(s.as_bytes())
     }
-}
 
+    /// Solves part 1 of an input given as a string.
+    fn solve_first_str(&self, s: &str) -> Result<Self::Output1> {
+        self.solve_first(&self.parse_str(s)?)
+    }
+
+    /// Solves part 2 of an input given as a string.
+    fn solve_second_str(&self, s: &str) -> Result<Self::Output2> {
+        self.solve_second(&self.parse_str(s)?)
+    }
+
+    /// Reads and parses the input of a day.
+    fn load_input(&self, year: i32, day: i32, source: &InputSource) -> std::result::Result<Self::Input, SolveError> {
+        match source.path(year, day) {
+            Some(file_path) => {
+                let f = File::open(&file_path)
+                    .map_err(|e| SolveError::new(day, Stage::Load, format!("Unable to open input file {}: {}", file_path, e)))?;
+                run_stage(day, Stage::Parse, || self.parse_input(f))
+            },
+            None => run_stage(day, Stage::Parse, || self.parse_input(io::stdin().lock())),
+        }
+    }
+
+    /// Runs a day, turning errors and panics into a [`SolveError`] of the stage they happened in.
+    fn solve(&self, day: i32, options: &Options) -> std::result::Result<DayResult, SolveError> {
+        let label = format!("Day {}", day);
+        let target = options.visualize.as_ref();
+        let frames = |stage: &str| format!("day{:02}-{}", day, stage);
+        let ((input, parse_memory), parse_time) = time_stage(&format!("{} parse", label), options.progress, || {
+            heap::measure(options.memory, || visualize::record(target, &frames("parse"), || self.load_input(options.year, day, &options.input)))
+        });
+        let input = input?;
+
+        let first = || time_stage(&format!("{} part 1", label), options.progress, || {
+            heap::measure(options.memory, || visualize::record(target, &frames("part1"), || {
+                run_stage(day, Stage::Part1, || self.solve_first(&input).map(|a| a.to_string()))
+            }))
+        });
+        let second = || time_stage(&format!("{} part 2", label), options.progress, || {
+            heap::measure(options.memory, || visualize::record(target, &frames("part2"), || {
+                run_stage(day, Stage::Part2, || self.solve_second(&input).map(|a| a.to_string()))
+            }))
+        });
+
+        let (first, second) = match options.part {
+            Some(1) => (Some(first()), None),
+            Some(_) if !Self::SECOND_PART => return Err(SolveError::new(day, Stage::Part2, "This day has no second part")),
+            Some(_) => (None, Some(second())),
+            None if !Self::SECOND_PART => (Some(first()), None),
+            None if options.parallel && Self::PARALLEL_PARTS => thread::scope(|s| {
+                let first = s.spawn(first);
+                let second = second();
+                // run_stage already caught any panic in the part itself
+                (Some(first.join().unwrap()), Some(second))
+            }),
+            None => (Some(first()), Some(second())),
+        };
+
+        let mut result = DayResult { parse_time, parse_memory, first: None, second: None };
+        if let Some(((value, memory), time)) = first {
+            result.first = Some(Answer { value: value?, time, memory });
+        }
+        if let Some(((value, memory), time)) = second {
+            result.second = Some(Answer { value: value?, time, memory });
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    answers::{self, Answers},
+    solver::{input_root, Options, Solver, DEFAULT_YEAR},
+};
+use std::fmt::Debug;
+
+// Examples are given as the puzzle input and the answer they should give
+pub fn check_first<S: Solver>(solver: &S, examples: &[(&str, S::Output1)])
+where
+    S::Output1: PartialEq + Debug,
+{
+    assert!(!examples.is_empty(), "No examples to check part 1 with");
+    for (i, (input, expected)) in examples.iter().enumerate() {
+        match solver.solve_first_str(input) {
+            Ok(answer) => assert_eq!(answer, *expected, "part 1 of example {}", i + 1),
+            Err(e) => panic!("Part 1 of example {} failed: {}", i + 1, e),
+        }
+    }
+}
+
+pub fn check_second<S: Solver>(solver: &S, examples: &[(&str, S::Output2)])
+where
+    S::Output2: PartialEq + Debug,
+{
+    assert!(!examples.is_empty(), "No examples to check part 2 with");
+    for (i, (input, expected)) in examples.iter().enumerate() {
+        match solver.solve_second_str(input) {
+            Ok(answer) => assert_eq!(answer, *expected, "part 2 of example {}", i + 1),
+            Err(e) => panic!("Part 2 of example {} failed: {}", i + 1, e),
+        }
+    }
+}
+
+/* Runs input/dayNN.txt and compares both parts with the expected answers. The
+ * tests calling this are marked #[ignore = "needs the full input"] so that they
+ * show up as ignored rather than passing without an input, run them with e.g.
+ *     cargo test --release -- --ignored full_input
+ */
+pub fn check_full_input<S: Solver>(solver: &S, day: i32) {
+    check_full_input_for(solver, DEFAULT_YEAR, day);
+}
+
+pub fn check_full_input_for<S: Solver>(solver: &S, year: i32, day: i32) {
+    check_parts(solver, year, day, None);
+}
+
+// For the days where only one of the parts gives the same answer every time
+pub fn check_full_input_part<S: Solver>(solver: &S, day: i32, part: u8) {
+    check_parts(solver, DEFAULT_YEAR, day, Some(part));
+}
+
+fn check_parts<S: Solver>(solver: &S, year: i32, day: i32, only: Option<u8>) {
+    let answers = Answers::load(&answers::default_file(&input_root(), year)).expect("Unable to load the expected answers");
+    let options = Options { year, part: only, quiet: true, ..Options::default() };
+    let result = solver.solve(day, &options).unwrap_or_else(|e| panic!("{}", e));
+    for (part, answer) in [(1, &result.first), (2, &result.second)] {
+        if only.is_some_and(|p| p != part) {
+            continue;
+        }
+        let answer = answer.as_ref().map(|a| &a.value);
+        assert_eq!(answer, answers.get(day, part), "day {} part {}", day, part);
+    }
+}
@@ -0,0 +1,135 @@
+//! Timing the stages of the days, and reporting the times.
+
+use crate::solver::{DayResult, SolveError};
+use std::{
+    io::{stderr, IsTerminal, Write},
+    sync::{atomic::{AtomicBool, Ordering}, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+// Quick stages finish before the progress line would show up at all
+const PROGRESS_DELAY: Duration = Duration::from_millis(500);
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Runs f and measures it, while it runs a line on stderr shows how long it has been going.
+pub fn time_stage<T>(label: &str, progress: bool, f: impl FnOnce() -> T) -> (T, Duration) {
+    let done = Arc::new(AtomicBool::new(false));
+    let start = Instant::now();
+
+    let spinner = if progress {
+        let done = done.clone();
+        let label = label.to_string();
+        // Only redraw the line in place on a terminal, otherwise just report slow stages
+        let redraw = stderr().is_terminal();
+        Some(thread::spawn(move || {
+            let mut shown = false;
+            let mut i = 0;
+            while !done.load(Ordering::SeqCst) {
+                thread::sleep(PROGRESS_INTERVAL);
+                if start.elapsed() >= PROGRESS_DELAY {
+                    shown = true;
+                    if !redraw {
+                        continue;
+                    }
+                    eprint!("\r{} {} running for {:.1}s", SPINNER[i % SPINNER.len()], label, start.elapsed().as_secs_f64());
+                    let _ = stderr().flush();
+                    i += 1;
+                }
+            }
+            if shown {
+                eprintln!("\r{} took {:.1}s{}", label, start.elapsed().as_secs_f64(), " ".repeat(10));
+            }
+        }))
+    } else {
+        None
+    };
+
+    let result = f();
+    let elapsed = start.elapsed();
+    done.store(true, Ordering::SeqCst);
+    if let Some(s) = spinner {
+        let _ = s.join();
+    }
+    (result, elapsed)
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// A time in milliseconds, or seconds once it takes that long. None is shown as -.
+pub fn format_time(d: Option<Duration>) -> String {
+    match d {
+        Some(d) if d.as_secs() >= 1 => format!("{:.2}s", d.as_secs_f64()),
+        Some(d) => format!("{:.2}ms", millis(d)),
+        None => String::from("-"),
+    }
+}
+
+/// A table of the times of each stage of each day, with the totals at the bottom.
+/// Days that failed to run are given as an error message instead of a result.
+pub fn table(results: &[(i32, Result<DayResult, SolveError>)]) -> String {
+    let mut output = format!("{:>5} {:>10} {:>10} {:>10} {:>10}\n", "Day", "Parse", "Part 1", "Part 2", "Total");
+    let mut totals = [Duration::default(); 4];
+
+    for (day, result) in results {
+        match result {
+            Ok(r) => {
+                let times = [
+                    Some(r.parse_time),
+                    r.first.as_ref().map(|a| a.time),
+                    r.second.as_ref().map(|a| a.time),
+                    Some(r.total_time()),
+                ];
+                for (total, time) in totals.iter_mut().zip(times.iter()) {
+                    *total += time.unwrap_or_default();
+                }
+                output.push_str(&format!("{:>5} {:>10} {:>10} {:>10} {:>10}\n", day,
+                                         format_time(times[0]), format_time(times[1]),
+                                         format_time(times[2]), format_time(times[3])));
+            },
+            Err(e) => output.push_str(&format!("{:>5} failed: {}\n", day, e)),
+        }
+    }
+
+    output.push_str(&format!("{:>5} {:>10} {:>10} {:>10} {:>10}\n", "Total",
+                             format_time(Some(totals[0])), format_time(Some(totals[1])),
+                             format_time(Some(totals[2])), format_time(Some(totals[3]))));
+    output
+}
+
+fn optional_millis(d: Option<Duration>, null: &str) -> String {
+    d.map_or(null.to_string(), |d| format!("{:.3}", millis(d)))
+}
+
+/// The times as CSV, with a line per day.
+pub fn csv(results: &[(i32, Result<DayResult, SolveError>)]) -> String {
+    let mut output = String::from("day,parse_ms,part1_ms,part2_ms,total_ms,ok\n");
+    for (day, result) in results {
+        match result {
+            Ok(r) => output.push_str(&format!("{},{:.3},{},{},{:.3},true\n", day, millis(r.parse_time),
+                                              optional_millis(r.first.as_ref().map(|a| a.time), ""),
+                                              optional_millis(r.second.as_ref().map(|a| a.time), ""),
+                                              millis(r.total_time()))),
+            Err(_) => output.push_str(&format!("{},,,,,false\n", day)),
+        }
+    }
+    output
+}
+
+/// The times as a JSON array, with an object per day.
+pub fn json(results: &[(i32, Result<DayResult, SolveError>)]) -> String {
+    let entries: Vec<String> = results.iter().map(|(day, result)| {
+        match result {
+            Ok(r) => format!("{{\"day\":{},\"parse_ms\":{:.3},\"part1_ms\":{},\"part2_ms\":{},\"total_ms\":{:.3},\"ok\":true}}",
+                             day, millis(r.parse_time),
+                             optional_millis(r.first.as_ref().map(|a| a.time), "null"),
+                             optional_millis(r.second.as_ref().map(|a| a.time), "null"),
+                             millis(r.total_time())),
+            Err(_) => format!("{{\"day\":{},\"ok\":false}}", day),
+        }
+    }).collect();
+    format!("[\n  {}\n]\n", entries.join(",\n  "))
+}
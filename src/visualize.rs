@@ -0,0 +1,405 @@
+//! Drawing grids and maps of points as they change while a day is solved, on
+//! the terminal in colour or as PPM and PNG images, one per frame.
+//!
+//! ```
+//! use aoc_2019::grid::Point;
+//! use aoc_2019::visualize::{Cell, Frame, Rgb};
+//! use std::collections::HashMap;
+//!
+//! let mut walls = HashMap::new();
+//! walls.insert(Point { x: 0, y: 0 }, true);
+//! walls.insert(Point { x: 2, y: 1 }, true);
+//! let frame = Frame::from_points(&walls, |_, wall| match wall {
+//!     Some(_) => Cell::new('#', Rgb::WHITE),
+//!     None => Cell::new('.', Rgb::GREY),
+//! });
+//! assert_eq!(frame.to_text(), "#..\n..#");
+//! ```
+//!
+//! Days call [`frame`] whenever there is something to show, which does nothing
+//! unless the stage is being [`record`]ed.
+
+use crate::grid::{Grid, Point};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, stderr, BufWriter, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// A colour, as red, green and blue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+#[allow(missing_docs)]
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(96, 96, 96);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(80, 200, 80);
+    pub const BLUE: Rgb = Rgb(50, 110, 230);
+    pub const YELLOW: Rgb = Rgb(240, 200, 40);
+}
+
+/// How a cell is drawn: a character on the terminal, in its colour, and a
+/// block of its colour in images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    /// The character.
+    pub glyph: char,
+    /// The colour.
+    pub colour: Rgb,
+}
+
+impl Cell {
+    /// A cell drawn as `glyph` in `colour`.
+    pub const fn new(glyph: char, colour: Rgb) -> Self {
+        Self { glyph, colour }
+    }
+
+    /// Nothing there.
+    pub const BLANK: Cell = Cell::new(' ', Rgb::BLACK);
+}
+
+/// One picture of an animation, a rectangle of cells stored row by row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    cells: Vec<Cell>,
+    /// The width.
+    pub w: usize,
+    /// The height.
+    pub h: usize,
+}
+
+impl Frame {
+    /// A frame of blank cells.
+    pub fn new(w: usize, h: usize) -> Self {
+        Self { cells: vec!(Cell::BLANK; w * h), w, h }
+    }
+
+    /// Draws each cell of `grid` with `cell`.
+    pub fn from_grid<T>(grid: &Grid<T>, cell: impl Fn(&T) -> Cell) -> Self
+    where
+        T: Clone + Default + TryFrom<u8>,
+    {
+        let mut frame = Self::new(grid.w, grid.h);
+        for y in 0..grid.h {
+            for x in 0..grid.w {
+                if let Some(t) = grid.get((x, y)) {
+                    frame.set(x, y, cell(t));
+                }
+            }
+        }
+        frame
+    }
+
+    /// Draws the smallest rectangle holding every point of `points`, with y
+    /// growing downwards. `cell` is also given the points in between that
+    /// aren't in the map, e.g. to show where a robot is.
+    pub fn from_points<T>(points: &HashMap<Point, T>, cell: impl Fn(Point, Option<&T>) -> Cell) -> Self {
+        if points.is_empty() {
+            return Self::new(0, 0);
+        }
+        let min_x = points.keys().map(|p| p.x).min().unwrap();
+        let max_x = points.keys().map(|p| p.x).max().unwrap();
+        let min_y = points.keys().map(|p| p.y).min().unwrap();
+        let max_y = points.keys().map(|p| p.y).max().unwrap();
+
+        let mut frame = Self::new((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let p = Point { x, y };
+                frame.set((x - min_x) as usize, (y - min_y) as usize, cell(p, points.get(&p)));
+            }
+        }
+        frame
+    }
+
+    /// Draws text such as the output of an ASCII capable program, a row per
+    /// line and as wide as the longest of them.
+    pub fn from_text(text: &str, cell: impl Fn(char) -> Cell) -> Self {
+        let rows: Vec<&str> = text.lines().collect();
+        let w = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+        let mut frame = Self::new(w, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                frame.set(x, y, cell(c));
+            }
+        }
+        frame
+    }
+
+    /// The same frame upside down, for maps where y grows upwards.
+    pub fn flipped(mut self) -> Self {
+        if self.w > 0 {
+            let rows: Vec<Vec<Cell>> = self.cells.chunks(self.w).rev().map(|r| r.to_vec()).collect();
+            self.cells = rows.concat();
+        }
+        self
+    }
+
+    /// Sets the cell at column `x` of row `y`, does nothing outside the frame.
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if x < self.w && y < self.h {
+            self.cells[x + y * self.w] = cell;
+        }
+    }
+
+    /// The cell at column `x` of row `y`.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x < self.w && y < self.h {
+            self.cells.get(x + y * self.w)
+        } else {
+            None
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        // chunks() can't take a width of 0
+        self.cells.chunks(self.w.max(1))
+    }
+
+    /// The characters without any colour, a line per row.
+    pub fn to_text(&self) -> String {
+        let rows: Vec<String> = self.rows().map(|r| r.iter().map(|c| c.glyph).collect()).collect();
+        rows.join("\n")
+    }
+
+    /// The characters in their colours as ANSI escape codes, a line per row.
+    pub fn to_ansi(&self) -> String {
+        let mut s = String::new();
+        for row in self.rows() {
+            let mut colour = None;
+            for cell in row {
+                if colour != Some(cell.colour) {
+                    let Rgb(r, g, b) = cell.colour;
+                    let _ = write!(s, "\x1b[38;2;{};{};{}m", r, g, b);
+                    colour = Some(cell.colour);
+                }
+                s.push(cell.glyph);
+            }
+            s.push_str("\x1b[0m\n");
+        }
+        s
+    }
+
+    // RGB bytes row by row, each cell a square of `scale` pixels
+    fn pixels(&self, scale: usize) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.w * self.h * scale * scale * 3);
+        for row in self.rows() {
+            for _ in 0..scale {
+                for cell in row {
+                    let Rgb(r, g, b) = cell.colour;
+                    for _ in 0..scale {
+                        pixels.extend_from_slice(&[r, g, b]);
+                    }
+                }
+            }
+        }
+        pixels
+    }
+
+    /// Writes the frame as a binary PPM image, each cell a square of `scale` pixels.
+    pub fn write_ppm<W: Write>(&self, mut w: W, scale: usize) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.w * scale, self.h * scale)?;
+        w.write_all(&self.pixels(scale))
+    }
+
+    /// Writes the frame as a PNG image, each cell a square of `scale` pixels.
+    pub fn write_png<W: Write>(&self, w: W, scale: usize) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(w, (self.w * scale) as u32, (self.h * scale) as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels(scale))?;
+        Ok(writer.finish()?)
+    }
+}
+
+/// The kinds of image frames can be saved as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary PPM, which is easy to read but big.
+    Ppm,
+    /// PNG.
+    Png,
+}
+
+impl ImageFormat {
+    /// The file extension.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// Where the frames of a stage go.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// Drawn over each other on stderr as an animation.
+    Terminal,
+    /// Saved as numbered images in a directory, e.g. `day13-part2-00042.png`.
+    Images {
+        /// The directory, created if it isn't there.
+        dir: String,
+        /// The kind of image.
+        format: ImageFormat,
+    },
+}
+
+/// How many pixels wide and high each cell is in the images.
+pub const IMAGE_SCALE: usize = 4;
+
+// Drawing every frame on the terminal would take far longer than the days themselves
+const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(40);
+
+struct Recording {
+    target: Target,
+    name: String,
+    frames: usize,
+    last_drawn: Option<Instant>,
+    // The latest frame that was too soon after the one before to draw
+    pending: Option<Frame>,
+}
+
+impl Recording {
+    fn add(&mut self, frame: Frame) -> io::Result<()> {
+        if frame.w == 0 || frame.h == 0 {
+            return Ok(());
+        }
+        self.frames += 1;
+        match &self.target {
+            Target::Terminal => {
+                if self.last_drawn.is_some_and(|t| t.elapsed() < MIN_FRAME_INTERVAL) {
+                    self.pending = Some(frame);
+                    return Ok(());
+                }
+                self.draw(&frame)
+            },
+            Target::Images { dir, format } => {
+                let path = format!("{}/{}-{:05}.{}", dir, self.name, self.frames, format.extension());
+                let w = BufWriter::new(File::create(&path)?);
+                match format {
+                    ImageFormat::Ppm => frame.write_ppm(w, IMAGE_SCALE),
+                    ImageFormat::Png => frame.write_png(w, IMAGE_SCALE),
+                }
+            },
+        }
+    }
+
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        let mut err = stderr().lock();
+        // Back to the top left and clear the rest, the first frame clears the whole screen
+        let clear = if self.last_drawn.is_none() { "\x1b[2J\x1b[H" } else { "\x1b[H\x1b[J" };
+        writeln!(err, "{}{}{} frame {}", clear, frame.to_ansi(), self.name, self.frames)?;
+        self.last_drawn = Some(Instant::now());
+        self.pending = None;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if let Some(frame) = self.pending.take() {
+            self.draw(&frame)?;
+        }
+        Ok(())
+    }
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+/// Whether a stage is being recorded, for days to skip work that is only
+/// needed for frames.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Adds a frame to the stage being recorded, `make` isn't called otherwise.
+/// A frame that can't be saved ends the recording with an error on stderr,
+/// the day itself carries on.
+pub fn frame(make: impl FnOnce() -> Frame) {
+    if !is_active() {
+        return;
+    }
+    let mut recording = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(r) = recording.as_mut() {
+        if let Err(e) = r.add(make()) {
+            eprintln!("Unable to save frame {} of {}: {}", r.frames, r.name, e);
+            *recording = None;
+            ACTIVE.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Runs `f`, sending the frames the day adds meanwhile to `target` under
+/// `name`, e.g. `day13-part2`. Only one stage can be recorded at a time.
+pub fn record<T>(target: Option<&Target>, name: &str, f: impl FnOnce() -> T) -> T {
+    let target = match target {
+        Some(t) => t,
+        None => return f(),
+    };
+    if let Target::Images { dir, .. } = target {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("Unable to create {} for the frames: {}", dir, e);
+            return f();
+        }
+    }
+
+    let recording = Recording { target: target.clone(), name: name.to_string(), frames: 0, last_drawn: None, pending: None };
+    *RECORDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(recording);
+    ACTIVE.store(true, Ordering::Relaxed);
+    let result = f();
+    ACTIVE.store(false, Ordering::Relaxed);
+
+    if let Some(mut r) = RECORDING.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        if let Err(e) = r.finish() {
+            eprintln!("Unable to draw the last frame of {}: {}", r.name, e);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> Frame {
+        let mut points = HashMap::new();
+        points.insert(Point { x: -1, y: 2 }, Rgb::RED);
+        points.insert(Point { x: 0, y: 3 }, Rgb::BLUE);
+        Frame::from_points(&points, |_, c| c.map_or(Cell::BLANK, |&c| Cell::new('#', c)))
+    }
+
+    #[test]
+    fn frames_of_points() {
+        let frame = frame();
+        assert_eq!((frame.w, frame.h), (2, 2));
+        assert_eq!(frame.get(0, 0), Some(&Cell::new('#', Rgb::RED)));
+        assert_eq!(frame.to_text(), "# \n #");
+        assert_eq!(frame.clone().flipped().to_text(), " #\n# ");
+        assert_eq!(Frame::from_text("#.\n#", |c| Cell::new(c, Rgb::WHITE)).to_text(), "#.\n# ");
+    }
+
+    #[test]
+    fn images() {
+        let mut ppm = vec!();
+        frame().write_ppm(&mut ppm, 2).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+        // The second pixel of the first row is still the top left cell
+        assert_eq!(&ppm[14..17], &[220, 50, 47]);
+
+        let mut png = vec!();
+        frame().write_png(&mut png, 1).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
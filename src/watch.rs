@@ -0,0 +1,298 @@
+use crate::{
+    answers::{self, Answers},
+    cli::Args,
+    solver::InputSource,
+    visualize::Target,
+};
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fs,
+    io::{stdin, BufRead},
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Editors tend to write a file in several goes, wait for them to finish
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+type Snapshot = BTreeMap<PathBuf, Option<SystemTime>>;
+
+// The scratch answers file of the runs, removed again once watching stops
+struct ScratchFile(PathBuf);
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn add_sources(dir: &Path, snapshot: &mut Snapshot) {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.is_dir() {
+            add_sources(&path, snapshot);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            let time = modified(&path);
+            snapshot.insert(path, time);
+        }
+    }
+}
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+// Everything a rebuild depends on, any solution can lean on the shared modules
+fn sources() -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    add_sources(&manifest_dir().join("src"), &mut snapshot);
+    let manifest = manifest_dir().join("Cargo.toml");
+    let time = modified(&manifest);
+    snapshot.insert(manifest, time);
+    snapshot
+}
+
+fn inputs(paths: &[PathBuf]) -> Snapshot {
+    paths.iter().map(|p| (p.clone(), modified(p))).collect()
+}
+
+fn rebuild(exe: &Path) -> bool {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let mut command = Command::new(cargo);
+    command.arg("build").arg("--manifest-path").arg(manifest_dir().join("Cargo.toml"));
+    // Keep building whichever profile we are running from
+    if exe.components().any(|c| c.as_os_str() == "release") {
+        command.arg("--release");
+    }
+    match command.status() {
+        Ok(status) => status.success(),
+        Err(e) => { eprintln!("Unable to run cargo: {}", e); false },
+    }
+}
+
+// Everything the runs are told, the output options are passed on as given
+fn day_args(cli: &Args, answers_file: &Path) -> Vec<OsString> {
+    let days: Vec<String> = cli.days.iter().map(|d| d.to_string()).collect();
+    let mut args: Vec<OsString> = vec!(days.join(",").into());
+    let mut push = |values: &[&str]| args.extend(values.iter().map(OsString::from));
+
+    push(&["--year", &cli.options.year.to_string(), "--jobs", &cli.jobs.to_string(), "--timings"]);
+    if let Some(part) = cli.options.part {
+        push(&["--part", &part.to_string()]);
+    }
+    if cli.options.quiet {
+        push(&["--quiet"]);
+    }
+    if cli.jsonl {
+        push(&["--jsonl"]);
+    }
+    if cli.options.memory {
+        push(&["--memory"]);
+    }
+    match &cli.options.visualize {
+        Some(Target::Terminal) => push(&["--visualize", "term"]),
+        Some(Target::Images { dir, format }) => push(&["--visualize", dir, "--frame-format", format.extension()]),
+        None => (),
+    }
+    if let Some(path) = &cli.timings_out {
+        push(&["--timings-out", path]);
+    }
+    match &cli.options.input {
+        InputSource::Dir(d) => push(&["--input-dir", d]),
+        InputSource::Path(p) => push(&["--input", p]),
+        InputSource::Stdin => (),
+    }
+    args.push("--answers".into());
+    args.push(answers_file.into());
+    args.push("--record-answers".into());
+    args
+}
+
+/* The days run in a fresh copy of the binary so a rebuild is picked up, it
+ * records its answers to a scratch answers file for comparing with the last run.
+ */
+fn run_days(cli: &Args, exe: &Path, answers_file: &Path) -> Option<Answers> {
+    let _ = fs::remove_file(answers_file);
+    let start = Instant::now();
+    if let Err(e) = Command::new(exe).args(day_args(cli, answers_file)).status() {
+        eprintln!("Unable to run the days: {}", e);
+        return None;
+    }
+    println!("Finished in {:.2}s", start.elapsed().as_secs_f64());
+    Answers::load_or_default(&answers_file.to_string_lossy()).ok()
+}
+
+fn compare(cli: &Args, previous: &Answers, current: &Answers) -> String {
+    let mut report = String::new();
+    for &day in cli.days.iter() {
+        for part in 1..=2 {
+            match (previous.get(day, part), current.get(day, part)) {
+                (Some(before), Some(after)) => match answers::diff(before, after) {
+                    None => report.push_str(&format!("Day {} part {}: unchanged\n", day, part)),
+                    Some(diff) => report.push_str(&format!("Day {} part {}: changed, expected is the last run's answer\n{}", day, part, diff)),
+                },
+                (Some(_), None) => report.push_str(&format!("Day {} part {}: no longer gives an answer\n", day, part)),
+                (None, Some(after)) => report.push_str(&format!("Day {} part {}: now gives {}\n", day, part, after)),
+                (None, None) => (),
+            }
+        }
+    }
+    report
+}
+
+// Signals once q is entered, the only way to stop watching that lets the scratch file be cleaned up
+fn quit_requests() -> Receiver<()> {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        for line in stdin().lock().lines() {
+            match line {
+                Ok(l) if l.trim() == "q" => { let _ = tx.send(()); return; },
+                Ok(_) => (),
+                Err(_) => return,
+            }
+        }
+    });
+    rx
+}
+
+// Polls the inputs and the sources, rebuilding first if it was the sources that changed
+pub fn run(cli: &Args) {
+    if matches!(cli.options.input, InputSource::Stdin) {
+        eprintln!("Can't watch stdin for changes");
+        process::exit(2);
+    }
+    let input_paths: Vec<PathBuf> = cli.days.iter()
+        .filter_map(|&d| cli.options.input.path(cli.options.year, d))
+        .map(PathBuf::from)
+        .collect();
+    let answers_file = ScratchFile(env::temp_dir().join(format!("aoc_watch_{}.txt", process::id())));
+    // Once cargo replaces the binary this process no longer knows where it came from
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => { eprintln!("Unable to find the running binary: {}", e); process::exit(2); },
+    };
+
+    let mut watched = (sources(), inputs(&input_paths));
+    let mut previous = run_days(cli, &exe, &answers_file.0);
+    println!("Watching {} and {} for changes, enter q to stop...",
+             input_paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "),
+             manifest_dir().join("src").display());
+
+    let quit = quit_requests();
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        if quit.try_recv().is_ok() {
+            break;
+        }
+        if (sources(), inputs(&input_paths)) == watched {
+            continue;
+        }
+        thread::sleep(SETTLE_TIME);
+        let now = (sources(), inputs(&input_paths));
+        let source_changed = now.0 != watched.0;
+        watched = now;
+
+        println!();
+        if source_changed {
+            println!("Sources changed, rebuilding...");
+            if !rebuild(&exe) {
+                println!("Build failed, waiting for more changes...");
+                continue;
+            }
+        } else {
+            println!("Input changed, running again...");
+        }
+
+        let current = run_days(cli, &exe, &answers_file.0);
+        if let (Some(previous), Some(current)) = (&previous, &current) {
+            print!("{}", compare(cli, previous, current));
+        }
+        if current.is_some() {
+            previous = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli;
+
+    fn parse(args: &str) -> Args {
+        cli::parse(&args.split(' ').map(String::from).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn forwards_options() {
+        let cli = parse("1,2 --quiet --jsonl --part 2 --memory --timings-out t.csv --input-dir inputs");
+        let args: Vec<_> = day_args(&cli, Path::new("scratch.txt")).iter().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(args.join(" "), format!("1,2 --year 2019 --jobs {} --timings --part 2 --quiet --jsonl --memory --timings-out t.csv \
+--input-dir inputs --answers scratch.txt --record-answers", cli.jobs));
+
+        let cli = parse("3 --visualize frames --frame-format ppm --input day3.txt");
+        let args: Vec<_> = day_args(&cli, Path::new("scratch.txt")).iter().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert!(args.join(" ").contains(" --visualize frames --frame-format ppm --input day3.txt --answers"));
+        assert!(!args.contains(&String::from("--quiet")) && !args.contains(&String::from("--jsonl")));
+    }
+
+    #[test]
+    fn compares_answers() {
+        let cli = parse("1,2,3");
+        let mut previous = Answers::default();
+        previous.set(1, 1, "12");
+        previous.set(1, 2, "34");
+        previous.set(2, 1, "#.\n.#");
+        let mut current = Answers::default();
+        current.set(1, 1, "12");
+        current.set(1, 2, "35");
+        current.set(3, 2, "9");
+        assert_eq!(compare(&cli, &previous, &current), "\
+Day 1 part 1: unchanged
+Day 1 part 2: changed, expected is the last run's answer
+    expected: 34
+    actual:   35
+Day 2 part 1: no longer gives an answer
+Day 3 part 2: now gives 9
+");
+    }
+
+    #[test]
+    fn snapshots_change() {
+        let dir = env::temp_dir().join(format!("aoc_watch_test_{}", process::id()));
+        fs::create_dir_all(dir.join("solutions")).unwrap();
+        fs::write(dir.join("main.rs"), "").unwrap();
+        fs::write(dir.join("solutions").join("day01.rs"), "").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let mut snapshot = Snapshot::new();
+        add_sources(&dir, &mut snapshot);
+        assert_eq!(snapshot.keys().cloned().collect::<Vec<_>>(), vec!(dir.join("main.rs"), dir.join("solutions").join("day01.rs")));
+        assert!(sources().contains_key(&manifest_dir().join("src").join("watch.rs")));
+
+        // A missing input is watched for appearing
+        let input = dir.join("day01.txt");
+        let before = inputs(std::slice::from_ref(&input));
+        assert_eq!(before.get(&input), Some(&None));
+        fs::write(&input, "1").unwrap();
+        let written = inputs(std::slice::from_ref(&input));
+        assert_ne!(written, before);
+        fs::File::options().write(true).open(&input).unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+        assert_ne!(inputs(std::slice::from_ref(&input)), written);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
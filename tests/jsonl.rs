@@ -0,0 +1,53 @@
+use std::{
+    env, fs,
+    process::{self, Command, Output, Stdio},
+};
+
+// The days that talk to their intcode programs in ASCII, some of them echo it while solving
+const CHATTY_DAYS: &str = "17,21,23,25";
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc_2019"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::null())
+        .output()
+        .expect("Unable to run the binary")
+}
+
+fn json_lines(output: &Output) -> Vec<String> {
+    let stdout = String::from_utf8(output.stdout.clone()).expect("stdout isn't UTF-8");
+    for line in stdout.lines() {
+        assert!(line.starts_with('{') && line.ends_with('}'), "{:?} isn't a JSON object, stdout was:\n{}", line, stdout);
+    }
+    stdout.lines().map(String::from).collect()
+}
+
+// A springdroid that gives its prompt and reports the damage straight away, whatever the script
+fn springdroid_program() -> String {
+    let mut program: Vec<String> = "Input instructions:\n".chars().flat_map(|c| vec!(String::from("104"), (c as u32).to_string())).collect();
+    program.extend(["104", "99999", "99"].iter().map(|v| v.to_string()));
+    program.join(",")
+}
+
+#[test]
+fn stdout_only_has_json_lines() {
+    let input = env::temp_dir().join(format!("aoc_jsonl_{}.txt", process::id()));
+    fs::write(&input, springdroid_program()).unwrap();
+    let output = run(&["21", "--input", &input.to_string_lossy(), "--jsonl"]);
+    fs::remove_file(&input).unwrap();
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 2);
+    for (part, line) in lines.iter().enumerate() {
+        assert!(line.starts_with(&format!("{{\"year\":2019,\"day\":21,\"part\":{},\"answer\":\"99999\",", part + 1)), "{:?}", line);
+    }
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Input instructions:"));
+}
+
+#[test]
+#[ignore = "needs the full input"]
+fn full_inputs_only_give_json_lines() {
+    // Two parts for each of the days, apart from the last day that only has one
+    assert_eq!(json_lines(&run(&[CHATTY_DAYS, "--jsonl"])).len(), 7);
+}